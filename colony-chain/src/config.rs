@@ -0,0 +1,205 @@
+use pdao_polkadot_interact::{
+    EnvSigner, GasLimits, KeystoreSigner, MnemonicSigner, ReadBackend, RetryPolicy, Signer,
+};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Configuration of the colony chain server, listing every chain it serves.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Config {
+    pub chains: Vec<ChainConfig>,
+}
+
+/// Configuration of a single colony chain.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChainConfig {
+    /// Name of the chain, also used as the name of its `serde_tc` object.
+    /// e.g. astar, shiden, shibuya.
+    pub name: String,
+    /// Websocket URI of the full node.
+    pub full_node_uri: String,
+    /// URL of the HTTP backend used by `pdao_polkadot_interact`.
+    pub http_server: String,
+    /// Where the relayer account's mnemonic comes from.
+    pub signer: SignerSource,
+    /// SS58 address format of the chain, 5 for the Astar family.
+    pub ss58_format: u16,
+    /// Decimal places of the native token in planck.
+    /// e.g. 18 for Shibuya, 12 for Rococo, 10 for Polkadot.
+    pub decimals: u32,
+    /// Symbol of the native token, e.g. ASTR, SDN, SBY.
+    pub native_symbol: String,
+    /// Addresses of the contracts deployed on the chain.
+    /// These take precedence over the deployment registry.
+    #[serde(default)]
+    pub contracts: ContractAddresses,
+    /// Path of the deployment registry file written by `pdao_polkadot_interact`.
+    pub registry: Option<PathBuf>,
+    /// Directory of the `<name>.contract` metadata bundles to check the calls to the contracts
+    /// against, which defaults to `deno-http-server/contracts` if it exists.
+    /// Contracts configured with their own metadata are checked against it instead.
+    pub metadata_dir: Option<PathBuf>,
+    /// Fungible tokens which the treasury holds, besides the native token.
+    #[serde(default)]
+    pub fungible_tokens: Vec<FungibleToken>,
+    /// Gas limits of the contract calls, which default to those of `pdao_polkadot_interact`.
+    #[serde(default)]
+    pub gas_limits: GasLimits,
+    /// Whether to read the chain from the full node directly or through the http server.
+    #[serde(default)]
+    pub read_backend: ReadBackend,
+    /// How the failed requests to the http server and the full node are retried.
+    #[serde(default)]
+    pub retry: RetryPolicy,
+    /// Seconds to wait for the transactions delivering messages to be finalized.
+    #[serde(default = "default_finality_timeout_secs")]
+    pub finality_timeout_secs: u64,
+    /// Settings of `check_connection`.
+    #[serde(default)]
+    pub connection: ConnectionConfig,
+}
+
+/// What `check_connection` expects from each component of the relay.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConnectionConfig {
+    /// Seconds to wait for each probe.
+    pub timeout_secs: u64,
+    /// Seconds since the last block after which the chain is considered stalled.
+    pub max_block_age_secs: u64,
+    /// Name of the chain reported by the full node, e.g. Shibuya Testnet.
+    pub chain: Option<String>,
+    /// Genesis hash of the chain, in `0x` prefixed hex.
+    pub genesis_hash: Option<String>,
+    /// Balance under which the relayer is considered to run out of fees.
+    pub min_relayer_balance: Decimal,
+}
+
+impl Default for ConnectionConfig {
+    fn default() -> Self {
+        Self {
+            timeout_secs: 10,
+            max_block_age_secs: 120,
+            chain: None,
+            genesis_hash: None,
+            min_relayer_balance: Decimal::ZERO,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FungibleToken {
+    pub symbol: String,
+    /// Address of the token contract.
+    pub address: String,
+    /// Decimal places of the token.
+    pub decimals: u32,
+}
+
+/// Source of the key which the relayer signs transactions with.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SignerSource {
+    /// The mnemonic is written in the config itself.
+    Mnemonic { mnemonic: String },
+    /// The mnemonic is read from the given environment variable.
+    Env { var: String },
+    /// An encrypted keystore exported from polkadot.js,
+    /// whose password is read from the given environment variable.
+    Keystore { path: PathBuf, password_var: String },
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ContractAddresses {
+    pub light_client: Option<String>,
+    pub treasury: Option<String>,
+    /// Custom contracts by their name.
+    /// The address may be followed by the options of `pdao_polkadot_interact::Contract`,
+    /// e.g. `<address>?metadata=contracts/my_token.contract`.
+    #[serde(default)]
+    pub custom: HashMap<String, String>,
+}
+
+fn default_finality_timeout_secs() -> u64 {
+    120
+}
+
+impl Config {
+    /// Read the config from a `.toml` or `.json` file.
+    pub fn read_from_file(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)?;
+        let config: Self = match path.extension().and_then(|x| x.to_str()) {
+            Some("toml") => toml::from_str(&content)?,
+            Some("json") => serde_json::from_str(&content)?,
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "unsupported config format: {}",
+                    path.display()
+                ))
+            }
+        };
+        config.validate()?;
+
+        Ok(config)
+    }
+
+    /// Check that the chains have distinct names, by which their objects are served.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        let mut names = HashSet::new();
+        for chain in &self.chains {
+            if !names.insert(chain.name.as_str()) {
+                return Err(anyhow::anyhow!(
+                    "chain {} is configured more than once",
+                    chain.name
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl SignerSource {
+    /// Return the signer of the relayer.
+    pub fn signer(&self) -> anyhow::Result<Box<dyn Signer>> {
+        match self {
+            SignerSource::Mnemonic { mnemonic } => Ok(Box::new(MnemonicSigner::new(mnemonic))),
+            SignerSource::Env { var } => Ok(Box::new(EnvSigner::new(var))),
+            SignerSource::Keystore { path, password_var } => {
+                let password = std::env::var(password_var).map_err(|_| {
+                    anyhow::anyhow!("environment variable {} is missing", password_var)
+                })?;
+                Ok(Box::new(KeystoreSigner::new(path, &password)))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_example_config() {
+        let config: Config = toml::from_str(include_str!("../config_example.toml")).unwrap();
+        let names: Vec<_> = config.chains.iter().map(|x| x.name.as_str()).collect();
+
+        assert_eq!(names, vec!["astar", "shiden", "shibuya", "local"]);
+        assert!(matches!(config.chains[2].signer, SignerSource::Env { .. }));
+        config.validate().unwrap();
+    }
+
+    #[test]
+    fn reject_duplicate_chain_names() {
+        let mut config: Config = toml::from_str(include_str!("../config_example.toml")).unwrap();
+        let mut duplicate = config.chains[0].clone();
+        duplicate.full_node_uri = "ws://127.0.0.1:9944".to_owned();
+        config.chains.push(duplicate);
+
+        let e = config.validate().unwrap_err();
+        assert_eq!(e.to_string(), "chain astar is configured more than once");
+    }
+}
@@ -0,0 +1,185 @@
+use crate::Astar;
+use pdao_colony_common::*;
+use pdao_polkadot_interact as interact;
+use std::fmt;
+use std::future::Future;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// A component of the relay which `check_connection` probes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Component {
+    /// The full node, which must be on the configured chain and keep producing blocks.
+    FullNode,
+    /// The HTTP backend of `pdao_polkadot_interact`.
+    HttpServer,
+    /// The relayer account, which must have enough balance to pay fees.
+    RelayerAccount,
+    /// A contract configured or deployed for the chain, by its address.
+    Contract(String),
+}
+
+impl fmt::Display for Component {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Component::FullNode => write!(f, "full node"),
+            Component::HttpServer => write!(f, "http server"),
+            Component::RelayerAccount => write!(f, "relayer account"),
+            Component::Contract(address) => write!(f, "contract {}", address),
+        }
+    }
+}
+
+/// Result of probing each component of the relay.
+#[derive(Debug, Clone)]
+pub struct ConnectionReport {
+    pub probes: Vec<(Component, Result<(), String>)>,
+}
+
+impl ConnectionReport {
+    pub fn is_healthy(&self) -> bool {
+        self.probes.iter().all(|(_, result)| result.is_ok())
+    }
+
+    /// Return the components which failed with their reasons.
+    pub fn failures(&self) -> impl Iterator<Item = (&Component, &String)> {
+        self.probes
+            .iter()
+            .filter_map(|(component, result)| result.as_ref().err().map(|e| (component, e)))
+    }
+}
+
+impl fmt::Display for ConnectionReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let failures: Vec<_> = self
+            .failures()
+            .map(|(component, e)| format!("{}: {}", component, e))
+            .collect();
+        if failures.is_empty() {
+            write!(f, "all components are healthy")
+        } else {
+            write!(f, "{}", failures.join("; "))
+        }
+    }
+}
+
+impl Astar {
+    /// Probe every component of the relay, each within the configured timeout.
+    pub async fn diagnose(&self) -> ConnectionReport {
+        let timeout = Duration::from_secs(self.config.connection.timeout_secs);
+        let (full_node, http_server, relayer_account) = tokio::join!(
+            probe(timeout, self.probe_full_node()),
+            probe(timeout, self.probe_http_server()),
+            probe(timeout, self.probe_relayer_account()),
+        );
+        let mut probes = vec![
+            (Component::FullNode, full_node),
+            (Component::HttpServer, http_server),
+            (Component::RelayerAccount, relayer_account),
+        ];
+
+        match self.contracts() {
+            Ok(contracts) => {
+                for contract in contracts {
+                    let result = probe(timeout, self.probe_contract(&contract)).await;
+                    let address = contract.address.unwrap_or(contract.name);
+                    probes.push((Component::Contract(address), result));
+                }
+            }
+            Err(e) => probes.push((Component::Contract("*".to_owned()), Err(e.to_string()))),
+        }
+
+        ConnectionReport { probes }
+    }
+
+    async fn probe_full_node(&self) -> Result<(), String> {
+        let config = &self.config.connection;
+        if config.chain.is_some() || config.genesis_hash.is_some() {
+            let chain_info = self
+                .client
+                .get_chain_info()
+                .await
+                .map_err(|e| e.to_string())?;
+            if let Some(chain) = config.chain.as_ref().filter(|x| **x != chain_info.chain) {
+                return Err(format!(
+                    "connected to {} instead of {}",
+                    chain_info.chain, chain
+                ));
+            }
+            if let Some(genesis_hash) = config
+                .genesis_hash
+                .as_ref()
+                .filter(|x| !x.eq_ignore_ascii_case(&chain_info.genesis_hash))
+            {
+                return Err(format!(
+                    "genesis hash {} does not match {}",
+                    chain_info.genesis_hash, genesis_hash
+                ));
+            }
+        }
+
+        // The height of a live chain advances, so its last block must be recent.
+        let block = self.get_last_block().await.map_err(|e| e.to_string())?;
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| e.to_string())?
+            .as_secs();
+        let age = now.saturating_sub(block.timestamp);
+        if age > config.max_block_age_secs {
+            return Err(format!(
+                "stalled at height {}, the last block is {} seconds old",
+                block.height, age
+            ));
+        }
+
+        Ok(())
+    }
+
+    async fn probe_http_server(&self) -> Result<(), String> {
+        self.client
+            .check_http_server()
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    async fn probe_relayer_account(&self) -> Result<(), String> {
+        let (address, balance) = self
+            .get_relayer_account_info()
+            .await
+            .map_err(|e| e.to_string())?;
+        if balance < self.config.connection.min_relayer_balance {
+            return Err(format!(
+                "{} has {} {}, less than {}",
+                address,
+                balance,
+                self.config.native_symbol,
+                self.config.connection.min_relayer_balance
+            ));
+        }
+
+        Ok(())
+    }
+
+    async fn probe_contract(&self, contract: &interact::Contract) -> Result<(), String> {
+        let address = contract.address().map_err(|e| e.to_string())?;
+        let exists = self
+            .client
+            .contract_exists(address)
+            .await
+            .map_err(|e| e.to_string())?;
+        if !exists {
+            return Err("no contract at the address".to_owned());
+        }
+
+        Ok(())
+    }
+}
+
+/// Run a probe, failing if it does not finish in time.
+async fn probe(
+    timeout: Duration,
+    probe: impl Future<Output = Result<(), String>>,
+) -> Result<(), String> {
+    tokio::time::timeout(timeout, probe)
+        .await
+        .unwrap_or_else(|_| Err(format!("timed out after {} seconds", timeout.as_secs())))
+}
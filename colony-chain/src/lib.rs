@@ -2,46 +2,452 @@ use async_trait::async_trait;
 use pdao_beacon_chain_common::message as pbc_message;
 use pdao_colony_common::*;
 use pdao_colony_contract_common::*;
+use pdao_polkadot_interact as interact;
+use pdao_polkadot_interact::registry::{Deployment, DeploymentRegistry};
 use rust_decimal::prelude::*;
-use rust_decimal_macros::dec;
 use std::collections::HashMap;
+use std::path::Path;
+use std::time::Duration;
 
-pub struct Astar {}
+pub mod config;
+pub mod connection;
+pub mod mock;
+
+use config::ChainConfig;
+
+/// A colony chain of the Astar family (Astar, Shiden, Shibuya or a local node).
+pub struct Astar {
+    pub config: ChainConfig,
+    client: interact::InteractClient,
+    signer: Box<dyn interact::Signer>,
+}
+
+impl Astar {
+    pub fn new(config: ChainConfig) -> anyhow::Result<Self> {
+        let mut client_config =
+            interact::ClientConfig::new(&config.full_node_uri, &config.http_server);
+        client_config.gas_limits = config.gas_limits;
+        client_config.decimals = config.decimals;
+        client_config.read_backend = config.read_backend;
+        client_config.retry = config.retry.clone();
+        client_config.registry = config.registry.clone();
+        let mut client = interact::InteractClient::new(client_config)?;
+        for (name, metadata) in read_metadata_dir(config.metadata_dir.as_deref())? {
+            client = client.with_metadata(&name, metadata);
+        }
+        let signer = config.signer.signer()?;
+        Ok(Self {
+            config,
+            client,
+            signer,
+        })
+    }
+
+    /// Read the deployment registry of the chain, which is empty if not configured.
+    fn deployments(&self) -> Result<Vec<Deployment>, Error> {
+        match &self.config.registry {
+            Some(path) => Ok(DeploymentRegistry::open(path)
+                .map_err(|e| Error::Unknown(format!("failed to read the registry: {}", e)))?
+                .deployments()
+                .to_vec()),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Return every contract on the chain at its address, the configured ones first,
+    /// followed by the last deployment of each other contract in the registry.
+    fn contracts(&self) -> Result<Vec<interact::Contract>, Error> {
+        let configured = &self.config.contracts;
+        let mut names = Vec::new();
+        if configured.light_client.is_some() {
+            names.push(interact::Contract::LIGHT_CLIENT);
+        }
+        if configured.treasury.is_some() {
+            names.push(interact::Contract::TREASURY);
+        }
+        let mut custom: Vec<_> = configured.custom.keys().map(String::as_str).collect();
+        custom.sort_unstable();
+        names.extend(custom);
+        let mut contracts = names
+            .into_iter()
+            .map(|name| self.contract(name))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let configured_count = contracts.len();
+        for deployment in self.deployments()?.into_iter().rev() {
+            if contracts
+                .iter()
+                .all(|x| x.name != deployment.contract().name)
+            {
+                contracts.push(deployment.contract().clone());
+            }
+        }
+        // Keep the deployed ones in the order of the registry.
+        contracts[configured_count..].reverse();
+
+        Ok(contracts)
+    }
+
+    /// Return the contract of the name at its address, from the config or the deployment registry.
+    fn contract(&self, name: &str) -> Result<interact::Contract, Error> {
+        let contracts = &self.config.contracts;
+        let configured = match name {
+            interact::Contract::LIGHT_CLIENT => contracts.light_client.clone(),
+            interact::Contract::TREASURY => contracts.treasury.clone(),
+            _ => contracts.custom.get(name).cloned(),
+        };
+        if let Some(configured) = configured {
+            // The address may be followed by the options of the contract, e.g. its metadata.
+            return format!("{}@{}", name, configured)
+                .parse()
+                .map_err(|e: interact::Error| Error::InvalidArgument(e.to_string()));
+        }
+        self.deployments()?
+            .into_iter()
+            .rev()
+            .find(|x| x.contract().name == name)
+            .map(|x| x.contract().clone())
+            .ok_or_else(|| Error::InvalidArgument(format!("{} is not deployed", name)))
+    }
+
+    /// Deliver an order of the beacon chain to a custom contract,
+    /// returning the transaction which executed it.
+    pub async fn execute_custom_order(
+        &self,
+        contract_name: &str,
+        message: pbc_message::Custom,
+        block_height: u64,
+        proof: MerkleProof,
+    ) -> Result<interact::ContractTx, Error> {
+        if contract_type(contract_name) != ContractType::Custom(contract_name.to_owned()) {
+            return Err(Error::InvalidArgument(format!(
+                "{} is not a custom contract",
+                contract_name
+            )));
+        }
+        let contract = self.contract(contract_name)?;
+        let order: CustomOrder = serde_json::from_str(&message.message)
+            .map_err(|e| Error::InvalidArgument(format!("invalid custom order: {}", e)))?;
+
+        // The contract verifies the order against the light client by itself.
+        let mut arguments = order.arguments;
+        arguments.push(block_height.into());
+        arguments.push(encode_argument("MerkleProof", &proof)?);
+        self.client
+            .execute_contract_method(self.signer.as_ref(), &contract, &order.method, arguments)
+            .await
+            .map_err(submission_error)
+    }
+
+    /// Wait until the transaction is finalized, so that the message it carries is delivered for good.
+    async fn wait_finalized(&self, tx: &interact::ContractTx) -> Result<(), Error> {
+        let deadline = Duration::from_secs(self.config.finality_timeout_secs);
+        let inclusion = self
+            .client
+            .track(&tx.tx_hash, tx.block_number)
+            .wait_finalized(deadline)
+            .await
+            .map_err(query_error)?;
+        match inclusion.dispatch_error {
+            Some(e) => Err(Error::TransactionRejected(format!(
+                "{} failed in block {}: {}",
+                tx.tx_hash, inclusion.block_hash, e
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// Read the IDs of the items in a PSP34 collection which the treasury holds.
+    async fn get_treasury_non_fungible_token_items(
+        &self,
+        treasury: &interact::Contract,
+        collection: &str,
+    ) -> Result<Vec<String>, Error> {
+        let mut items: Vec<String> = Vec::new();
+        let mut previous_page = Vec::new();
+        for _ in 0..MAX_NFT_PAGES {
+            let offset = items.len().to_string();
+            let limit = NFT_PAGE_SIZE.to_string();
+            let page = self
+                .client
+                .query_contract_state(
+                    treasury,
+                    "non_fungible_token_items",
+                    vec![collection, &offset, &limit],
+                )
+                .await
+                .map_err(query_error)?
+                .output;
+            // A backend which ignores the offset would give the same page forever.
+            if page.is_empty() || page == previous_page {
+                return Ok(items);
+            }
+            let last_page = page.len() < NFT_PAGE_SIZE;
+            items.extend_from_slice(&page);
+            if last_page {
+                return Ok(items);
+            }
+            previous_page = page;
+        }
+        Err(Error::Unknown(format!(
+            "the treasury holds more than {} items of {}",
+            MAX_NFT_PAGES * NFT_PAGE_SIZE,
+            collection
+        )))
+    }
+
+    /// Read the sequence of a contract which receives messages from the beacon chain.
+    async fn get_contract_sequence(&self, contract: &interact::Contract) -> Result<u64, Error> {
+        match contract_type(&contract.name) {
+            ContractType::LightClient | ContractType::Treasury => {
+                let result = self
+                    .client
+                    .query_contract_state(contract, "sequence", Vec::new())
+                    .await
+                    .map_err(query_error)?;
+                single_output(&result)?
+                    .parse()
+                    .map_err(|e| Error::Unknown(format!("invalid sequence: {}", e)))
+            }
+            // Other contracts do not receive messages in order.
+            _ => Ok(0),
+        }
+    }
+}
+
+/// Directory of the metadata bundles which the http server reads, relative to where it runs.
+const DEFAULT_METADATA_DIR: &str = "deno-http-server/contracts";
+
+/// Read the `<name>.contract` metadata bundles in the directory by the names of the contracts.
+fn read_metadata_dir(
+    dir: Option<&Path>,
+) -> anyhow::Result<Vec<(String, interact::ContractMetadata)>> {
+    let dir = match dir {
+        Some(dir) => dir,
+        None if Path::new(DEFAULT_METADATA_DIR).is_dir() => Path::new(DEFAULT_METADATA_DIR),
+        None => return Ok(Vec::new()),
+    };
+    let mut bundles = Vec::new();
+    for entry in std::fs::read_dir(dir)
+        .map_err(|e| anyhow::anyhow!("failed to read {}: {}", dir.display(), e))?
+    {
+        let path = entry?.path();
+        if path.extension().and_then(|x| x.to_str()) != Some("contract") {
+            continue;
+        }
+        let name = match path.file_stem().and_then(|x| x.to_str()) {
+            Some(name) => name.to_owned(),
+            None => continue,
+        };
+        let metadata = interact::ContractMetadata::from_file(&path)
+            .map_err(|e| anyhow::anyhow!("invalid metadata {}: {}", path.display(), e))?;
+        bundles.push((name, metadata));
+    }
+
+    Ok(bundles)
+}
+
+/// An order to a custom contract, carried by `pbc_message::Custom` as JSON.
+///
+/// e.g. `{"method": "execute", "arguments": [{"type": "u64", "value": "0x0500000000000000"}]}`.
+///
+/// The block height and the Merkle proof of the order are always appended to the arguments,
+/// so the message must take them last, as `u64` and `MerkleProof`, and verify the order
+/// against the light client before acting on it.
+/// The proof is SCALE encoded as described in `encode_argument`.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct CustomOrder {
+    /// Name of the contract message to call.
+    pub method: String,
+    /// SCALE encoded arguments of the message, before the block height and the proof.
+    #[serde(default)]
+    pub arguments: Vec<interact::Argument>,
+}
+
+/// Number of the NFTs to query at once from the treasury.
+const NFT_PAGE_SIZE: usize = 100;
+
+/// Number of the pages of NFTs to query at most from the treasury for a collection.
+const MAX_NFT_PAGES: usize = 1000;
+
+/// Return the output of a query which returns a single value.
+fn single_output(result: &interact::ContractQuery) -> Result<&str, Error> {
+    match result.output.as_slice() {
+        [output] => Ok(output),
+        _ => Err(Error::Unknown(format!(
+            "expected a single output from {}, got {:?}",
+            result.message_name, result.output
+        ))),
+    }
+}
+
+/// Encode a value of the common crates into an argument of a contract message,
+/// of the type with the given name in the metadata.
+///
+/// Those types implement only `Serialize`, so they are SCALE encoded through it,
+/// into the bytes which `Decode` of the same types in the contracts reads,
+/// as `pdao_polkadot_interact::scale` describes.
+/// These are the `Header` and the `BlockFinalizationProof` given to `update` of the light client,
+/// the transfer messages of the treasury, and the `MerkleProof` given to the treasury
+/// and the custom orders.
+fn encode_argument<T: serde::Serialize>(
+    type_name: &str,
+    value: &T,
+) -> Result<interact::Argument, Error> {
+    interact::Argument::serialized(type_name, value)
+        .map_err(|e| Error::InvalidArgument(format!("failed to encode {}: {}", type_name, e)))
+}
+
+/// Convert an error of `pdao_polkadot_interact` from reading the chain.
+fn query_error(e: interact::Error) -> Error {
+    match e {
+        interact::Error::Transport(_)
+        | interact::Error::HttpStatus(_)
+        | interact::Error::Backend(_)
+        | interact::Error::WebSocket(_)
+        | interact::Error::Rpc { .. } => Error::ConnectionError(e.to_string()),
+        interact::Error::MissingSigner(_)
+        | interact::Error::InvalidAddress(_)
+        | interact::Error::InvalidCall(_)
+        | interact::Error::InvalidContract(_) => Error::InvalidArgument(e.to_string()),
+        _ => Error::Unknown(e.to_string()),
+    }
+}
+
+/// Convert an error of `pdao_polkadot_interact` from submitting a transaction.
+fn submission_error(e: interact::Error) -> Error {
+    match e {
+        interact::Error::Backend(_) | interact::Error::ContractReverted(_) => {
+            Error::TransactionRejected(e.to_string())
+        }
+        e => query_error(e),
+    }
+}
+
+/// Convert a failure of a treasury transfer into an error describing why it was rejected.
+fn treasury_transfer_error(e: interact::Error) -> Error {
+    match &e {
+        interact::Error::ContractReverted(reason) => match reason.as_str() {
+            "InvalidProof" => Error::InvalidArgument(format!("invalid proof: {}", e)),
+            "InvalidSequence" | "AlreadyExecuted" => {
+                Error::InvalidArgument(format!("message already delivered: {}", e))
+            }
+            "InsufficientBalance" => {
+                Error::TransactionRejected(format!("insufficient treasury balance: {}", e))
+            }
+            _ => submission_error(e),
+        },
+        _ => submission_error(e),
+    }
+}
+
+/// Check that the transaction emitted the given event.
+fn expect_event(tx: &interact::ContractTx, name: &str) -> Result<(), Error> {
+    if tx.events.iter().any(|x| x.name == name) {
+        Ok(())
+    } else {
+        Err(Error::Unknown(format!(
+            "{} is not emitted by the transaction {}",
+            name, tx.tx_hash
+        )))
+    }
+}
+
+/// Check whether an item ID returned by the treasury is the given token index.
+/// PSP34 IDs are returned as JSON like `{"u64":1}`, while token indices are plain.
+fn is_same_nft_item(item: &str, token_index: &str) -> bool {
+    if item == token_index {
+        return true;
+    }
+    match serde_json::from_str::<serde_json::Value>(item) {
+        Ok(serde_json::Value::Object(id)) if id.len() == 1 => match id.values().next() {
+            Some(serde_json::Value::String(x)) => x == token_index,
+            Some(serde_json::Value::Number(x)) => token_index == x.to_string(),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// Convert an amount in planck into the amount of the token with the given decimals.
+/// The http server gives the amounts above 2^53 in `0x` prefixed hex.
+pub fn planck_to_decimal(planck: &str, decimals: u32) -> Result<Decimal, Error> {
+    let planck = match planck.strip_prefix("0x") {
+        Some(hex) => i128::from_str_radix(hex, 16),
+        None => planck.parse::<i128>(),
+    }
+    .map_err(|e| Error::InvalidArgument(format!("invalid amount {}: {}", planck, e)))?;
+    Decimal::try_from_i128_with_scale(planck, decimals)
+        .map(|x| x.normalize())
+        .map_err(|e| Error::InvalidArgument(format!("amount out of range: {}", e)))
+}
+
+fn contract_type(name: &str) -> ContractType {
+    match name {
+        interact::Contract::LIGHT_CLIENT => ContractType::LightClient,
+        interact::Contract::TREASURY => ContractType::Treasury,
+        _ => ContractType::Custom(name.to_owned()),
+    }
+}
 
 #[async_trait]
 impl ColonyChain for Astar {
     async fn get_chain_name(&self) -> String {
-        "astar".to_owned()
+        self.config.name.clone()
     }
 
     async fn get_last_block(&self) -> Result<Block, Error> {
+        let height = self
+            .client
+            .get_current_height()
+            .await
+            .map_err(query_error)?;
+        let block = self.client.get_block(height).await.map_err(query_error)?;
+
         Ok(Block {
-            height: 0,
-            timestamp: 0,
+            height,
+            // The timestamp of the block is given in milliseconds.
+            timestamp: block.timestamp / 1000,
         })
     }
 
     async fn check_connection(&self) -> Result<(), Error> {
-        Ok(())
+        let report = self.diagnose().await;
+        if report.is_healthy() {
+            Ok(())
+        } else {
+            Err(Error::ConnectionError(report.to_string()))
+        }
     }
 
     async fn get_contract_list(&self) -> Result<Vec<ContractInfo>, Error> {
-        Ok(vec![
-            ContractInfo {
-                address: "0xabcd".to_owned(),
-                contract_type: ContractType::LightClient,
-                sequence: 0,
-            },
-            ContractInfo {
-                address: "0x1234".to_owned(),
-                contract_type: ContractType::Treasury,
-                sequence: 0,
-            },
-        ])
+        let mut contracts = Vec::new();
+        for contract in self.contracts()? {
+            contracts.push(ContractInfo {
+                address: contract.address().map_err(query_error)?.to_owned(),
+                contract_type: contract_type(&contract.name),
+                sequence: self.get_contract_sequence(&contract).await?,
+            });
+        }
+
+        Ok(contracts)
     }
 
     async fn get_relayer_account_info(&self) -> Result<(String, Decimal), Error> {
-        Ok(("0x12341234".to_owned(), dec!(12.34)))
+        let address = self
+            .client
+            .get_address(self.signer.as_ref(), self.config.ss58_format)
+            .await
+            .map_err(query_error)?;
+        let account = self
+            .client
+            .query_account(&address)
+            .await
+            .map_err(query_error)?;
+        // Reserved funds cannot pay fees.
+        let balance = planck_to_decimal(&account.free_native_token, self.config.decimals)?;
+
+        Ok((address, balance))
     }
 
     async fn get_light_client_header(&self) -> Result<Header, Error> {
@@ -49,56 +455,357 @@ impl ColonyChain for Astar {
     }
 
     async fn get_treasury_fungible_token_balance(&self) -> Result<HashMap<String, Decimal>, Error> {
-        Ok(vec![
-            ("Bitcoin".to_owned(), dec!(123.45)),
-            ("Ether".to_owned(), dec!(444.44)),
-        ]
-        .into_iter()
-        .collect())
+        let treasury = self.contract(interact::Contract::TREASURY)?;
+        let mut balances = HashMap::new();
+
+        let account = self
+            .client
+            .query_account(treasury.address().map_err(query_error)?)
+            .await
+            .map_err(query_error)?;
+        balances.insert(
+            self.config.native_symbol.clone(),
+            planck_to_decimal(&account.native_token, self.config.decimals)?,
+        );
+
+        for token in &self.config.fungible_tokens {
+            let result = self
+                .client
+                .query_contract_state(&treasury, "fungible_token_balance", vec![&token.address])
+                .await
+                .map_err(query_error)?;
+            let balance = planck_to_decimal(single_output(&result)?, token.decimals)?;
+            balances.insert(token.symbol.clone(), balance);
+        }
+
+        Ok(balances)
     }
 
     async fn get_treasury_non_fungible_token_balance(
         &self,
     ) -> Result<Vec<(String, String)>, Error> {
-        Ok(vec![
-            ("BAYC".to_owned(), "1".to_owned()),
-            ("Sandbox Land".to_owned(), "2".to_owned()),
-        ])
+        let treasury = self.contract(interact::Contract::TREASURY)?;
+        let collections = self
+            .client
+            .query_contract_state(&treasury, "non_fungible_token_collections", Vec::new())
+            .await
+            .map_err(query_error)?
+            .output;
+
+        let mut tokens = Vec::new();
+        for collection in collections {
+            tokens.extend(
+                self.get_treasury_non_fungible_token_items(&treasury, &collection)
+                    .await?
+                    .into_iter()
+                    .map(|item| (collection.clone(), item)),
+            );
+        }
+
+        Ok(tokens)
     }
 
     async fn update_light_client(
         &self,
-        _header: light_client::Header,
-        _proof: light_client::BlockFinalizationProof,
+        header: light_client::Header,
+        proof: light_client::BlockFinalizationProof,
     ) -> Result<(), Error> {
-        Ok(())
+        let light_client = self.contract(interact::Contract::LIGHT_CLIENT)?;
+        let header = encode_argument("Header", &header)?;
+        let proof = encode_argument("BlockFinalizationProof", &proof)?;
+        // The backend waits for the transaction to be included in a block,
+        // and fails if the light client rejects the proof.
+        let tx = self
+            .client
+            .execute_contract_method(
+                self.signer.as_ref(),
+                &light_client,
+                "update",
+                vec![header, proof],
+            )
+            .await
+            .map_err(submission_error)?;
+
+        self.wait_finalized(&tx).await
     }
 
     async fn transfer_treasury_fungible_token(
         &self,
-        _message: pbc_message::FungibleTokenTransfer,
-        _block_height: u64,
-        _proof: MerkleProof,
+        message: pbc_message::FungibleTokenTransfer,
+        block_height: u64,
+        proof: MerkleProof,
     ) -> Result<(), Error> {
-        Ok(())
+        let treasury = self.contract(interact::Contract::TREASURY)?;
+        let message = encode_argument("FungibleTokenTransfer", &message)?;
+        let proof = encode_argument("MerkleProof", &proof)?;
+        let tx = self
+            .client
+            .execute_contract_method(
+                self.signer.as_ref(),
+                &treasury,
+                "transfer_fungible_token",
+                vec![message, block_height.into(), proof],
+            )
+            .await
+            .map_err(treasury_transfer_error)?;
+
+        expect_event(&tx, "FungibleTokenTransferred")?;
+        self.wait_finalized(&tx).await
     }
 
     async fn transfer_treasury_non_fungible_token(
         &self,
-        _message: pbc_message::NonFungibleTokenTransfer,
-        _block_height: u64,
-        _proof: MerkleProof,
+        message: pbc_message::NonFungibleTokenTransfer,
+        block_height: u64,
+        proof: MerkleProof,
     ) -> Result<(), Error> {
+        let treasury = self.contract(interact::Contract::TREASURY)?;
+        let receiver: interact::AccountId = message
+            .receiver_address
+            .parse()
+            .map_err(|e: interact::Error| Error::InvalidArgument(e.to_string()))?;
+        // The treasury lists the items by their PSP34 IDs, e.g. `{"u64":7}` for `7`,
+        // which the collection is asked for the owner of after the transfer.
+        let id: interact::psp34::Id = self
+            .get_treasury_non_fungible_token_items(&treasury, &message.collection_address)
+            .await?
+            .iter()
+            .find(|x| is_same_nft_item(x, &message.token_index))
+            .ok_or_else(|| {
+                Error::InvalidArgument(format!(
+                    "{} of {} is not held by the treasury",
+                    message.token_index, message.collection_address
+                ))
+            })?
+            .parse()
+            .map_err(query_error)?;
+
+        let encoded_message = encode_argument("NonFungibleTokenTransfer", &message)?;
+        let proof = encode_argument("MerkleProof", &proof)?;
+        let tx = self
+            .client
+            .execute_contract_method(
+                self.signer.as_ref(),
+                &treasury,
+                "transfer_non_fungible_token",
+                vec![encoded_message, block_height.into(), proof],
+            )
+            .await
+            .map_err(treasury_transfer_error)?;
+        expect_event(&tx, "NonFungibleTokenTransferred")?;
+        self.wait_finalized(&tx).await?;
+
+        // Make sure that the treasury does not hold the token anymore,
+        // and that the receiver owns it now according to the collection itself.
+        let items = self
+            .get_treasury_non_fungible_token_items(&treasury, &message.collection_address)
+            .await?;
+        if items
+            .iter()
+            .any(|x| is_same_nft_item(x, &message.token_index))
+        {
+            return Err(Error::Unknown(format!(
+                "{} of {} is still held by the treasury after {}",
+                message.token_index, message.collection_address, tx.tx_hash
+            )));
+        }
+        let owner = self
+            .client
+            .psp34_owner_of(&message.collection_address, &id)
+            .await
+            .map_err(query_error)?;
+        if owner != Some(receiver) {
+            return Err(Error::Unknown(format!(
+                "{} of {} is owned by {:?} instead of {} after {}",
+                message.token_index,
+                message.collection_address,
+                owner,
+                message.receiver_address,
+                tx.tx_hash
+            )));
+        }
+
         Ok(())
     }
 
     async fn deliver_custom_order(
         &self,
-        _contract_name: &str,
-        _message: pbc_message::Custom,
-        _block_height: u64,
-        _proof: MerkleProof,
+        contract_name: &str,
+        message: pbc_message::Custom,
+        block_height: u64,
+        proof: MerkleProof,
     ) -> Result<(), Error> {
-        Ok(())
+        let tx = self
+            .execute_custom_order(contract_name, message, block_height, proof)
+            .await?;
+        self.wait_finalized(&tx).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn list_configured_and_deployed_contracts() {
+        let registry = std::env::temp_dir().join(format!(
+            "pdao-colony-{}-list_configured_and_deployed_contracts.json",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&registry);
+        let mut deployments = DeploymentRegistry::open(&registry).unwrap();
+        for (name, address) in [
+            (interact::Contract::TREASURY, "treasury-deployed"),
+            (interact::Contract::LIGHT_CLIENT, "light-client-old"),
+            ("my_token", "my-token-deployed"),
+            (interact::Contract::LIGHT_CLIENT, "light-client-new"),
+        ] {
+            let deploy = interact::ContractDeploy {
+                contract_name: name.to_owned(),
+                contract_addr: address.to_owned(),
+                code_hash: "0x6ecc".to_owned(),
+                tx_hash: "0x1234".to_owned(),
+                block_number: 100,
+            };
+            deployments
+                .append(Deployment::new(interact::Contract::new(name), &deploy))
+                .unwrap();
+        }
+
+        let mut config: config::Config =
+            toml::from_str(include_str!("../config_example.toml")).unwrap();
+        let mut config = config.chains.remove(2);
+        config.registry = Some(registry.clone());
+        config.contracts.treasury = Some("treasury-configured".to_owned());
+        let astar = Astar::new(config).unwrap();
+        let contracts: Vec<_> = astar
+            .contracts()
+            .unwrap()
+            .into_iter()
+            .map(|x| (x.name.clone(), x.address.unwrap()))
+            .collect();
+        std::fs::remove_file(&registry).unwrap();
+
+        let expected = [
+            ("treasury", "treasury-configured"),
+            (
+                "simple_counter",
+                "aeQtHkL7ENgfVYwiMZNYgz2GeRa75B2cSaVz1AFjZ9omRtv",
+            ),
+            ("my_token", "my-token-deployed"),
+            ("light_client", "light-client-new"),
+        ];
+        assert_eq!(
+            contracts,
+            expected
+                .iter()
+                .map(|(name, address)| (name.to_string(), address.to_string()))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn convert_planck_to_decimal() {
+        // Shibuya, Rococo and Polkadot.
+        assert_eq!(
+            planck_to_decimal("12340000000000000000", 18).unwrap(),
+            dec!(12.34)
+        );
+        assert_eq!(planck_to_decimal("1500000000000", 12).unwrap(), dec!(1.5));
+        assert_eq!(planck_to_decimal("1", 10).unwrap(), dec!(0.0000000001));
+        assert_eq!(planck_to_decimal("0", 18).unwrap(), Decimal::ZERO);
+        assert!(planck_to_decimal("12.34", 18).is_err());
+        // Treasury balances of tokens with 18 decimals.
+        assert_eq!(
+            planck_to_decimal("2500000000000000000000", 18).unwrap(),
+            dec!(2500)
+        );
+        assert_eq!(
+            planck_to_decimal("0x878678326eac900000", 18).unwrap(),
+            dec!(2500)
+        );
+        assert_eq!(
+            planck_to_decimal("0xab407c9eb0520000", 18).unwrap(),
+            dec!(12.34)
+        );
+        assert_eq!(planck_to_decimal("0xde0b6b3a7640000", 18).unwrap(), dec!(1));
+        assert!(planck_to_decimal("0xzz", 18).is_err());
+        assert!(planck_to_decimal(&u128::MAX.to_string(), 18).is_err());
+    }
+
+    #[test]
+    fn compare_nft_items() {
+        assert!(is_same_nft_item("7", "7"));
+        assert!(is_same_nft_item(r#"{"u64":7}"#, "7"));
+        assert!(is_same_nft_item(r#"{"bytes":"0x0102"}"#, "0x0102"));
+        assert!(!is_same_nft_item(r#"{"u64":17}"#, "7"));
+        assert!(!is_same_nft_item("17", "7"));
+    }
+
+    #[tokio::test]
+    async fn check_calls_against_metadata_dir() {
+        let mut config: config::Config =
+            toml::from_str(include_str!("../config_example.toml")).unwrap();
+        let mut config = config.chains.remove(2);
+        config.metadata_dir =
+            Some(concat!(env!("CARGO_MANIFEST_DIR"), "/../deno-http-server/contracts").into());
+        let astar = Astar::new(config).unwrap();
+        let order = pbc_message::Custom {
+            message: r#"{"method": "no_such_method"}"#.to_owned(),
+        };
+        let proof = MerkleProof {
+            root: "root".to_owned(),
+        };
+
+        // Rejected before the signer, which is not set, or the http server is needed.
+        match astar
+            .execute_custom_order(interact::Contract::SIMPLE_COUNTER, order, 10, proof)
+            .await
+        {
+            Err(Error::InvalidArgument(e)) => {
+                assert!(e.contains("no message no_such_method"), "{}", e)
+            }
+            result => panic!("unexpected result: {:?}", result.map(|x| x.tx_hash)),
+        }
+    }
+
+    #[test]
+    fn encode_arguments_in_scale() {
+        let argument = encode_argument("Header", &(7u64, "root")).unwrap();
+        assert_eq!(argument.type_name, "Header");
+        assert_eq!(argument.encoded().unwrap(), b"\x07\0\0\0\0\0\0\0\x10root");
+    }
+
+    #[test]
+    fn classify_treasury_transfer_errors() {
+        let revert = |reason: &str| {
+            treasury_transfer_error(interact::Error::ContractReverted(reason.to_owned()))
+        };
+
+        assert!(matches!(
+            revert("InvalidProof"),
+            Error::InvalidArgument(msg) if msg.starts_with("invalid proof")
+        ));
+        assert!(matches!(
+            revert("AlreadyExecuted"),
+            Error::InvalidArgument(msg) if msg.starts_with("message already delivered")
+        ));
+        assert!(matches!(
+            revert("InsufficientBalance"),
+            Error::TransactionRejected(msg) if msg.starts_with("insufficient treasury balance")
+        ));
+        assert!(matches!(
+            treasury_transfer_error(interact::Error::Backend(
+                "Error: dispatch error: contracts.OutOfGas".to_owned()
+            )),
+            Error::TransactionRejected(_)
+        ));
+        assert!(matches!(
+            treasury_transfer_error(interact::Error::MissingSigner(
+                "SIGNER_MNEMONIC is not set".to_owned()
+            )),
+            Error::InvalidArgument(_)
+        ));
     }
 }
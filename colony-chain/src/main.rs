@@ -1,4 +1,5 @@
 use pdao_colony_common::*;
+use pdao_polkadot_colony_chain::config::Config;
 use pdao_polkadot_colony_chain::*;
 use std::sync::Arc;
 
@@ -9,15 +10,26 @@ async fn main() {
         .unwrap_or_else(|| "80".to_owned())
         .parse::<u16>()
         .unwrap();
+    let config_path = std::env::args()
+        .nth(2)
+        .unwrap_or_else(|| "config.toml".to_owned());
+    let config = Config::read_from_file(&config_path).expect("failed to read the config");
     println!("RUN ON PORT {}", port);
     serde_tc::http::run_server(
         port,
-        vec![(
-            "astar".to_owned(),
-            serde_tc::http::create_http_object(Arc::new(Astar {}) as Arc<dyn ColonyChain>),
-        )]
-        .into_iter()
-        .collect(),
+        config
+            .chains
+            .into_iter()
+            .map(|chain| {
+                println!("SERVE {} ({})", chain.name, chain.full_node_uri);
+                let name = chain.name.clone();
+                let astar = Astar::new(chain).expect("failed to create the colony chain");
+                (
+                    name,
+                    serde_tc::http::create_http_object(Arc::new(astar) as Arc<dyn ColonyChain>),
+                )
+            })
+            .collect(),
     )
     .await;
 }
@@ -0,0 +1,556 @@
+use async_trait::async_trait;
+use pdao_beacon_chain_common::message as pbc_message;
+use pdao_colony_common::*;
+use pdao_colony_contract_common::*;
+use rust_decimal::prelude::*;
+use std::collections::{BTreeSet, HashMap};
+use std::sync::{Mutex, MutexGuard};
+
+/// Checks a finalization proof given to the light client.
+pub type FinalizationProofVerifier =
+    Box<dyn Fn(&light_client::Header, &light_client::BlockFinalizationProof) -> bool + Send + Sync>;
+/// Checks a Merkle proof of a message from the beacon chain.
+pub type MerkleProofVerifier = Box<dyn Fn(u64, &MerkleProof) -> bool + Send + Sync>;
+
+/// The simulated state of a `MockColonyChain`.
+#[derive(Debug, Clone, Default)]
+pub struct MockState {
+    /// Height of the last block, which increases by one for every accepted transaction.
+    pub height: u64,
+    /// Timestamp of the last block in seconds.
+    pub timestamp: u64,
+    pub relayer_address: String,
+    pub relayer_balance: Decimal,
+    /// Serialized header of the light client, `None` until the first update.
+    pub light_client_header: Option<Header>,
+    pub light_client_sequence: u64,
+    pub treasury_sequence: u64,
+    pub fungible_token_balance: HashMap<String, Decimal>,
+    /// (collection, item) pairs held by the treasury.
+    pub non_fungible_token_balance: Vec<(String, String)>,
+    /// Names of the deployed custom contracts, which report no sequence like on `Astar`,
+    /// as their orders are not delivered in order.
+    pub custom_contracts: BTreeSet<String>,
+    /// (contract name, order) pairs delivered to the custom contracts.
+    pub custom_orders: Vec<(String, String)>,
+}
+
+/// A colony chain which keeps its state in memory, for testing without a network.
+///
+/// It accepts every proof unless verifiers are given, but still rejects the
+/// transfers before the first light client update, replayed messages and
+/// transfers exceeding the treasury balance.
+pub struct MockColonyChain {
+    name: String,
+    /// Seconds between the simulated blocks.
+    block_time: u64,
+    state: Mutex<MockState>,
+    finalization_proof_verifier: FinalizationProofVerifier,
+    merkle_proof_verifier: MerkleProofVerifier,
+}
+
+impl MockColonyChain {
+    pub fn new(name: &str, state: MockState) -> Self {
+        Self {
+            name: name.to_owned(),
+            block_time: 12,
+            state: Mutex::new(state),
+            finalization_proof_verifier: Box::new(|_, _| true),
+            merkle_proof_verifier: Box::new(|_, _| true),
+        }
+    }
+
+    pub fn with_finalization_proof_verifier(mut self, verifier: FinalizationProofVerifier) -> Self {
+        self.finalization_proof_verifier = verifier;
+        self
+    }
+
+    pub fn with_merkle_proof_verifier(mut self, verifier: MerkleProofVerifier) -> Self {
+        self.merkle_proof_verifier = verifier;
+        self
+    }
+
+    /// Return a snapshot of the current state.
+    pub fn state(&self) -> MockState {
+        self.lock().clone()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, MockState> {
+        self.state.lock().expect("mock state is poisoned")
+    }
+
+    /// Check a message delivered to a contract with the given sequence.
+    fn verify_message(
+        &self,
+        state: &MockState,
+        contract_sequence: u64,
+        expected_sequence: u64,
+        block_height: u64,
+        proof: &MerkleProof,
+    ) -> Result<(), Error> {
+        if state.light_client_header.is_none() {
+            return Err(Error::TransactionRejected(
+                "light client is not initialized".to_owned(),
+            ));
+        }
+        if contract_sequence != expected_sequence {
+            return Err(Error::InvalidArgument(format!(
+                "message already delivered or out of order: expected sequence {}, got {}",
+                expected_sequence, contract_sequence
+            )));
+        }
+        if !(self.merkle_proof_verifier)(block_height, proof) {
+            return Err(Error::InvalidArgument("invalid proof".to_owned()));
+        }
+        Ok(())
+    }
+
+    /// Produce a new block containing the transaction.
+    fn produce_block(&self, state: &mut MockState) {
+        state.height += 1;
+        state.timestamp += self.block_time;
+    }
+}
+
+#[async_trait]
+impl ColonyChain for MockColonyChain {
+    async fn get_chain_name(&self) -> String {
+        self.name.clone()
+    }
+
+    async fn get_last_block(&self) -> Result<Block, Error> {
+        let state = self.lock();
+        Ok(Block {
+            height: state.height,
+            timestamp: state.timestamp,
+        })
+    }
+
+    async fn check_connection(&self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    async fn get_contract_list(&self) -> Result<Vec<ContractInfo>, Error> {
+        let state = self.lock();
+        let mut contracts = vec![
+            ContractInfo {
+                address: "light_client".to_owned(),
+                contract_type: ContractType::LightClient,
+                sequence: state.light_client_sequence,
+            },
+            ContractInfo {
+                address: "treasury".to_owned(),
+                contract_type: ContractType::Treasury,
+                sequence: state.treasury_sequence,
+            },
+        ];
+        contracts.extend(state.custom_contracts.iter().map(|name| ContractInfo {
+            address: name.clone(),
+            contract_type: ContractType::Custom(name.clone()),
+            sequence: 0,
+        }));
+        Ok(contracts)
+    }
+
+    async fn get_relayer_account_info(&self) -> Result<(String, Decimal), Error> {
+        let state = self.lock();
+        Ok((state.relayer_address.clone(), state.relayer_balance))
+    }
+
+    async fn get_light_client_header(&self) -> Result<Header, Error> {
+        self.lock()
+            .light_client_header
+            .clone()
+            .ok_or_else(|| Error::Unknown("light client is not initialized".to_owned()))
+    }
+
+    async fn get_treasury_fungible_token_balance(&self) -> Result<HashMap<String, Decimal>, Error> {
+        Ok(self.lock().fungible_token_balance.clone())
+    }
+
+    async fn get_treasury_non_fungible_token_balance(
+        &self,
+    ) -> Result<Vec<(String, String)>, Error> {
+        Ok(self.lock().non_fungible_token_balance.clone())
+    }
+
+    async fn update_light_client(
+        &self,
+        header: light_client::Header,
+        proof: light_client::BlockFinalizationProof,
+    ) -> Result<(), Error> {
+        if !(self.finalization_proof_verifier)(&header, &proof) {
+            return Err(Error::TransactionRejected(
+                "invalid finalization proof".to_owned(),
+            ));
+        }
+        let header = serde_json::to_string(&header)
+            .map_err(|e| Error::InvalidArgument(format!("invalid header: {}", e)))?;
+        let mut state = self.lock();
+        state.light_client_header = Some(header);
+        state.light_client_sequence += 1;
+        self.produce_block(&mut state);
+        Ok(())
+    }
+
+    async fn transfer_treasury_fungible_token(
+        &self,
+        message: pbc_message::FungibleTokenTransfer,
+        block_height: u64,
+        proof: MerkleProof,
+    ) -> Result<(), Error> {
+        let mut state = self.lock();
+        self.verify_message(
+            &state,
+            message.contract_sequence,
+            state.treasury_sequence,
+            block_height,
+            &proof,
+        )?;
+        let balance = state
+            .fungible_token_balance
+            .get_mut(&message.token_id)
+            .filter(|balance| **balance >= message.amount)
+            .ok_or_else(|| {
+                Error::TransactionRejected(format!(
+                    "insufficient treasury balance of {}",
+                    message.token_id
+                ))
+            })?;
+        *balance -= message.amount;
+        state.treasury_sequence += 1;
+        self.produce_block(&mut state);
+        Ok(())
+    }
+
+    async fn transfer_treasury_non_fungible_token(
+        &self,
+        message: pbc_message::NonFungibleTokenTransfer,
+        block_height: u64,
+        proof: MerkleProof,
+    ) -> Result<(), Error> {
+        let mut state = self.lock();
+        self.verify_message(
+            &state,
+            message.contract_sequence,
+            state.treasury_sequence,
+            block_height,
+            &proof,
+        )?;
+        let index = state
+            .non_fungible_token_balance
+            .iter()
+            .position(|(collection, item)| {
+                collection == &message.collection_address && item == &message.token_index
+            })
+            .ok_or_else(|| {
+                Error::TransactionRejected(format!(
+                    "{} of {} is not held by the treasury",
+                    message.token_index, message.collection_address
+                ))
+            })?;
+        state.non_fungible_token_balance.remove(index);
+        state.treasury_sequence += 1;
+        self.produce_block(&mut state);
+        Ok(())
+    }
+
+    async fn deliver_custom_order(
+        &self,
+        contract_name: &str,
+        message: pbc_message::Custom,
+        block_height: u64,
+        proof: MerkleProof,
+    ) -> Result<(), Error> {
+        let mut state = self.lock();
+        if !state.custom_contracts.contains(contract_name) {
+            return Err(Error::InvalidArgument(format!(
+                "{} is not a deployed contract",
+                contract_name
+            )));
+        }
+        // Custom orders carry no sequence, so only the proof is checked.
+        self.verify_message(&state, 0, 0, block_height, &proof)?;
+        state
+            .custom_orders
+            .push((contract_name.to_owned(), message.message));
+        self.produce_block(&mut state);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn header(height: u64) -> light_client::Header {
+        light_client::Header {
+            height,
+            hash: format!("{:064x}", height),
+        }
+    }
+
+    fn finalization_proof() -> light_client::BlockFinalizationProof {
+        light_client::BlockFinalizationProof {
+            signatures: vec!["signature".to_owned()],
+        }
+    }
+
+    fn merkle_proof() -> MerkleProof {
+        MerkleProof {
+            root: "root".to_owned(),
+        }
+    }
+
+    fn fungible_transfer(sequence: u64, amount: Decimal) -> pbc_message::FungibleTokenTransfer {
+        pbc_message::FungibleTokenTransfer {
+            colony_chain: "mock".to_owned(),
+            contract_sequence: sequence,
+            amount,
+            receiver_address: "receiver".to_owned(),
+            token_id: "PDAO".to_owned(),
+        }
+    }
+
+    fn non_fungible_transfer(
+        sequence: u64,
+        token_index: &str,
+    ) -> pbc_message::NonFungibleTokenTransfer {
+        pbc_message::NonFungibleTokenTransfer {
+            colony_chain: "mock".to_owned(),
+            contract_sequence: sequence,
+            collection_address: "collection".to_owned(),
+            token_index: token_index.to_owned(),
+            receiver_address: "receiver".to_owned(),
+        }
+    }
+
+    /// A chain at height 10, whose light client is updated, whose treasury holds
+    /// 100 PDAO and two NFTs, and which has `simple_counter` deployed.
+    fn initialized_chain() -> MockColonyChain {
+        MockColonyChain::new(
+            "mock",
+            MockState {
+                height: 10,
+                timestamp: 1_000,
+                light_client_header: Some("header".to_owned()),
+                fungible_token_balance: vec![("PDAO".to_owned(), dec!(100))].into_iter().collect(),
+                non_fungible_token_balance: vec![
+                    ("collection".to_owned(), "1".to_owned()),
+                    ("collection".to_owned(), "2".to_owned()),
+                ],
+                custom_contracts: vec!["simple_counter".to_owned()].into_iter().collect(),
+                ..MockState::default()
+            },
+        )
+    }
+
+    #[tokio::test]
+    async fn reject_replayed_messages() {
+        let chain = initialized_chain();
+        chain
+            .transfer_treasury_fungible_token(fungible_transfer(0, dec!(10)), 1, merkle_proof())
+            .await
+            .unwrap();
+        // Delivered again, or skipping a sequence.
+        for sequence in [0, 2] {
+            assert!(matches!(
+                chain
+                    .transfer_treasury_fungible_token(
+                        fungible_transfer(sequence, dec!(10)),
+                        1,
+                        merkle_proof()
+                    )
+                    .await,
+                Err(Error::InvalidArgument(_))
+            ));
+        }
+        chain
+            .transfer_treasury_non_fungible_token(non_fungible_transfer(1, "1"), 2, merkle_proof())
+            .await
+            .unwrap();
+
+        let state = chain.state();
+        assert_eq!(state.treasury_sequence, 2);
+        assert_eq!(state.fungible_token_balance["PDAO"], dec!(90));
+    }
+
+    #[tokio::test]
+    async fn reject_transfers_over_the_balance() {
+        let chain = initialized_chain();
+        assert!(matches!(
+            chain
+                .transfer_treasury_fungible_token(
+                    fungible_transfer(0, dec!(100.5)),
+                    1,
+                    merkle_proof()
+                )
+                .await,
+            Err(Error::TransactionRejected(_))
+        ));
+        // The whole balance can be sent.
+        chain
+            .transfer_treasury_fungible_token(fungible_transfer(0, dec!(100)), 1, merkle_proof())
+            .await
+            .unwrap();
+
+        let state = chain.state();
+        assert_eq!(state.treasury_sequence, 1);
+        assert_eq!(state.fungible_token_balance["PDAO"], Decimal::ZERO);
+    }
+
+    #[tokio::test]
+    async fn remove_transferred_nfts() {
+        let chain = initialized_chain();
+        chain
+            .transfer_treasury_non_fungible_token(non_fungible_transfer(0, "1"), 1, merkle_proof())
+            .await
+            .unwrap();
+        assert_eq!(
+            chain
+                .get_treasury_non_fungible_token_balance()
+                .await
+                .unwrap(),
+            vec![("collection".to_owned(), "2".to_owned())]
+        );
+        // The treasury does not hold it anymore.
+        assert!(matches!(
+            chain
+                .transfer_treasury_non_fungible_token(
+                    non_fungible_transfer(1, "1"),
+                    1,
+                    merkle_proof()
+                )
+                .await,
+            Err(Error::TransactionRejected(_))
+        ));
+        assert_eq!(chain.state().treasury_sequence, 1);
+    }
+
+    #[tokio::test]
+    async fn reject_transfers_before_the_light_client_update() {
+        let chain = MockColonyChain::new(
+            "mock",
+            MockState {
+                fungible_token_balance: vec![("PDAO".to_owned(), dec!(100))].into_iter().collect(),
+                ..MockState::default()
+            },
+        );
+        assert!(chain.get_light_client_header().await.is_err());
+        assert!(matches!(
+            chain
+                .transfer_treasury_fungible_token(fungible_transfer(0, dec!(1)), 1, merkle_proof())
+                .await,
+            Err(Error::TransactionRejected(_))
+        ));
+
+        chain
+            .update_light_client(header(1), finalization_proof())
+            .await
+            .unwrap();
+        assert_eq!(
+            chain.get_light_client_header().await.unwrap(),
+            serde_json::to_string(&header(1)).unwrap()
+        );
+        chain
+            .transfer_treasury_fungible_token(fungible_transfer(0, dec!(1)), 1, merkle_proof())
+            .await
+            .unwrap();
+        assert_eq!(chain.state().light_client_sequence, 1);
+    }
+
+    #[tokio::test]
+    async fn reject_invalid_proofs() {
+        let chain = initialized_chain()
+            .with_finalization_proof_verifier(Box::new(|header, _| header.height > 5))
+            .with_merkle_proof_verifier(Box::new(|height, _| height <= 5));
+
+        assert!(matches!(
+            chain
+                .update_light_client(header(5), finalization_proof())
+                .await,
+            Err(Error::TransactionRejected(_))
+        ));
+        assert_eq!(chain.get_light_client_header().await.unwrap(), "header");
+        chain
+            .update_light_client(header(6), finalization_proof())
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            chain
+                .transfer_treasury_fungible_token(fungible_transfer(0, dec!(1)), 6, merkle_proof())
+                .await,
+            Err(Error::InvalidArgument(_))
+        ));
+        assert!(matches!(
+            chain
+                .deliver_custom_order(
+                    "simple_counter",
+                    pbc_message::Custom {
+                        message: "{}".to_owned()
+                    },
+                    6,
+                    merkle_proof()
+                )
+                .await,
+            Err(Error::InvalidArgument(_))
+        ));
+        chain
+            .transfer_treasury_fungible_token(fungible_transfer(0, dec!(1)), 5, merkle_proof())
+            .await
+            .unwrap();
+
+        let state = chain.state();
+        assert_eq!(state.light_client_sequence, 1);
+        assert_eq!(state.treasury_sequence, 1);
+        assert!(state.custom_orders.is_empty());
+    }
+
+    #[tokio::test]
+    async fn advance_blocks_with_transactions() {
+        let chain = initialized_chain();
+        let block = chain.get_last_block().await.unwrap();
+        assert_eq!((block.height, block.timestamp), (10, 1_000));
+
+        chain
+            .update_light_client(header(1), finalization_proof())
+            .await
+            .unwrap();
+        chain
+            .transfer_treasury_fungible_token(fungible_transfer(0, dec!(1)), 1, merkle_proof())
+            .await
+            .unwrap();
+        let block = chain.get_last_block().await.unwrap();
+        assert_eq!((block.height, block.timestamp), (12, 1_024));
+
+        // Custom contracts keep no sequence, as on `Astar`.
+        chain
+            .deliver_custom_order(
+                "simple_counter",
+                pbc_message::Custom {
+                    message: "{}".to_owned(),
+                },
+                1,
+                merkle_proof(),
+            )
+            .await
+            .unwrap();
+        let contracts = chain.get_contract_list().await.unwrap();
+        assert_eq!(
+            contracts[2].contract_type,
+            ContractType::Custom("simple_counter".to_owned())
+        );
+        assert_eq!(contracts[2].sequence, 0);
+        let block = chain.get_last_block().await.unwrap();
+        assert_eq!(block.height, 13);
+
+        // Rejected transactions produce no block.
+        let _ = chain
+            .transfer_treasury_fungible_token(fungible_transfer(0, dec!(1)), 1, merkle_proof())
+            .await;
+        let block = chain.get_last_block().await.unwrap();
+        assert_eq!(block.height, 13);
+    }
+}
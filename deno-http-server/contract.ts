@@ -24,15 +24,35 @@ export const SIMPLE_COUNTER_TX = {
 export type SIMPLE_COUNTER_TX_METHOD =
   typeof SIMPLE_COUNTER_TX[keyof typeof SIMPLE_COUNTER_TX];
 
-export const LIGHT_CLIENT = {} as const;
+// The light client and the treasury take the types of `pdao-colony-contract-common`
+// and `pdao-beacon-chain-common`, e.g. headers, proofs and transfer messages,
+// SCALE encoded as `#[derive(Encode)]` of the same shape would, by their type names,
+// as `pdao_polkadot_interact::scale` describes.
+
+export const LIGHT_CLIENT = {
+  GET_SEQUENCE: "getSequence",
+} as const;
 export type LIGHT_CLIENT_QUERY = typeof LIGHT_CLIENT[keyof typeof LIGHT_CLIENT];
 
-export const LIGHT_CLIENT_TX = {} as const;
+export const LIGHT_CLIENT_TX = {
+  // update(header: Header, proof: BlockFinalizationProof)
+  UPDATE: "update",
+} as const;
 export type LIGHT_CLIENT_TX_METHOD =
   typeof LIGHT_CLIENT_TX[keyof typeof LIGHT_CLIENT_TX];
 
-export const TREASURY = {} as const;
+export const TREASURY = {
+  GET_SEQUENCE: "getSequence",
+  GET_FUNGIBLE_TOKEN_BALANCE: "getFungibleTokenBalance",
+  GET_NON_FUNGIBLE_TOKEN_COLLECTIONS: "getNonFungibleTokenCollections",
+  GET_NON_FUNGIBLE_TOKEN_ITEMS: "getNonFungibleTokenItems",
+} as const;
 export type TREASURY_QUERY = typeof TREASURY[keyof typeof TREASURY];
 
-export const TREASURY_TX = {} as const;
+// Each takes (message, blockHeight: u64, proof: MerkleProof), of which the message is
+// `FungibleTokenTransfer` or `NonFungibleTokenTransfer` of the beacon chain.
+export const TREASURY_TX = {
+  TRANSFER_FUNGIBLE_TOKEN: "transferFungibleToken",
+  TRANSFER_NON_FUNGIBLE_TOKEN: "transferNonFungibleToken",
+} as const;
 export type TREASURY_TX_METHOD = typeof TREASURY_TX[keyof typeof TREASURY_TX];
@@ -9,11 +9,13 @@ import {
   deployWithCodeHash,
   deployWithContractName,
   getBlockInfo,
+  getCurrentHeight,
   getFreeBalance,
   getPairFromSeed,
   getPairFromSeedWithSS58,
   getReservedBalance,
   getTotalBalance,
+  getTxInclusion,
   query,
   sendContractTx,
   transferNativeToken,
@@ -85,7 +87,7 @@ Deno.test({
       SIMPLE_COUNTER_ADDR,
       "count",
     );
-    const txHash = await sendContractTx(
+    const { txHash } = await sendContractTx(
       RPC_ENDPOINT.SHIBUYA,
       TESTNET_MNEMONIC.SHIBUYA,
       CONTRACT.SIMPLE_COUNTER,
@@ -123,7 +125,7 @@ Deno.test({
       SIMPLE_COUNTER_ADDR,
       "count",
     );
-    const txHash = await sendContractTx(
+    const { txHash } = await sendContractTx(
       RPC_ENDPOINT.SHIBUYA,
       TESTNET_MNEMONIC.SHIBUYA,
       CONTRACT.SIMPLE_COUNTER,
@@ -141,7 +143,7 @@ Deno.test({
     // assert(parseInt(countAfterInc) - parseInt(prevCount) === 1);
 
     //decrement
-    const txHash2 = await sendContractTx(
+    const { txHash: txHash2 } = await sendContractTx(
       RPC_ENDPOINT.SHIBUYA,
       TESTNET_MNEMONIC.SHIBUYA,
       CONTRACT.SIMPLE_COUNTER,
@@ -177,7 +179,7 @@ Deno.test({
   async fn() {
     const SIMPLE_COUNTER_ADDR =
       "Xt1CVcr4nTd3oKrPk85xJWLTCMwGZa6KyxGo2kTGf2NjzLf";
-    const txHash = await sendContractTx(
+    const { txHash } = await sendContractTx(
       RPC_ENDPOINT.SHIBUYA,
       TESTNET_MNEMONIC.SHIBUYA,
       CONTRACT.SIMPLE_COUNTER,
@@ -186,7 +188,7 @@ Deno.test({
       [],
     );
     console.log("txHash: ", txHash);
-    const txHash2 = await sendContractTx(
+    const { txHash: txHash2 } = await sendContractTx(
       RPC_ENDPOINT.SHIBUYA,
       TESTNET_MNEMONIC.SHIBUYA,
       CONTRACT.SIMPLE_COUNTER,
@@ -220,7 +222,7 @@ Deno.test({
       "auth",
     );
     const prevAuthList = prevList?.toString().split(",");
-    const txHash = await sendContractTx(
+    const { txHash } = await sendContractTx(
       RPC_ENDPOINT.SHIBUYA,
       TESTNET_MNEMONIC.SHIBUYA,
       CONTRACT.SIMPLE_COUNTER,
@@ -236,7 +238,7 @@ Deno.test({
       "auth",
     );
     const authListAfterAdd = listAfterAdd?.toString().split(",");
-    const txHash2 = await sendContractTx(
+    const { txHash: txHash2 } = await sendContractTx(
       RPC_ENDPOINT.SHIBUYA,
       TESTNET_MNEMONIC.SHIBUYA,
       CONTRACT.SIMPLE_COUNTER,
@@ -297,7 +299,7 @@ Deno.test({
     const FIRST_AUTH_ADDR =
       getPairFromSeedWithSS58(TESTNET_MNEMONIC.SHIBUYA, SS58_FORMAT.SHIBUYA)
         .address;
-    const contractTxHash: string = await sendContractTx(
+    const { txHash: contractTxHash } = await sendContractTx(
       RPC_ENDPOINT.SHIBUYA,
       TESTNET_MNEMONIC.SHIBUYA,
       CONTRACT.SIMPLE_COUNTER,
@@ -363,7 +365,7 @@ Deno.test({
     const FIRST_AUTH_ADDR =
       getPairFromSeedWithSS58(TESTNET_MNEMONIC.SHIBUYA, SS58_FORMAT.SHIBUYA)
         .address;
-    const contractTxHash: string = await sendContractTx(
+    const { txHash: contractTxHash } = await sendContractTx(
       RPC_ENDPOINT.SHIBUYA,
       TESTNET_MNEMONIC.SHIBUYA,
       CONTRACT.SIMPLE_COUNTER,
@@ -451,3 +453,37 @@ Deno.test({
   sanitizeResources: false,
   sanitizeOps: false,
 });
+
+Deno.test({
+  name: "token transfer test: find the transfer by its returned hash",
+  async fn() {
+    const AMOUNT = 1000000000000000n; // 0.001 SBY
+    const receiverAddr: string =
+      getPairFromSeedWithSS58(TESTNET_MNEMONIC.ROCOCO, SS58_FORMAT.SHIBUYA)
+        .address;
+    const fromHeight = await getCurrentHeight(RPC_ENDPOINT.SHIBUYA);
+    const txHash: string = await transferNativeToken(
+      RPC_ENDPOINT.SHIBUYA,
+      TESTNET_MNEMONIC.SHIBUYA,
+      receiverAddr,
+      AMOUNT,
+    );
+    // The block which includes the signed extrinsic must be found by the returned hash.
+    for (let attempt = 0; attempt < 10; attempt++) {
+      await new Promise((resolve) => setTimeout(resolve, 6000));
+      const { inclusion } = await getTxInclusion(
+        RPC_ENDPOINT.SHIBUYA,
+        txHash,
+        fromHeight,
+      );
+      if (inclusion !== null) {
+        assert(inclusion.blockNumber >= fromHeight);
+        assert(inclusion.dispatchError === null);
+        return;
+      }
+    }
+    assert(false, `${txHash} is not included in a block`);
+  },
+  sanitizeResources: false,
+  sanitizeOps: false,
+});
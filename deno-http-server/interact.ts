@@ -4,28 +4,23 @@ import {
   WsProvider,
 } from "https://deno.land/x/polkadot@0.0.9/api/mod.ts";
 import {
+  Abi,
   BlueprintPromise,
   CodePromise,
   ContractPromise,
 } from "https://deno.land/x/polkadot@0.0.9/api-contract/mod.ts";
+import { hexToU8a } from "https://deno.land/x/polkadot@0.0.9/util/mod.ts";
 import fs from "https://deno.land/std@0.115.1/node/fs/promises.ts";
-import { KeyringPair } from "https://deno.land/x/polkadot@0.0.9/keyring/types.ts";
+import {
+  KeyringPair,
+  KeyringPair$Json,
+} from "https://deno.land/x/polkadot@0.0.9/keyring/types.ts";
 import { SubmittableExtrinsic } from "https://deno.land/x/polkadot@0.0.9/api/submittable/types.ts";
 import { CodeSubmittableResult } from "https://deno.land/x/polkadot@0.0.9/api-contract/base/Code.ts";
 import { BlueprintSubmittableResult } from "https://deno.land/x/polkadot@0.0.9/api-contract/base/Blueprint.ts";
 import { ApiTypes } from "https://deno.land/x/polkadot@0.0.9/api-base/types/index.ts";
 import type {} from "https://deno.land/x/polkadot@0.0.9/api-augment/mod.ts";
 import type { AnyJson } from "https://deno.land/x/polkadot@0.0.9/types-codec/types/index.ts";
-import { ABI_PATH, ABI_PATH_OPTIONS } from "./abi.ts";
-import {
-  CONTRACT,
-  LIGHT_CLIENT_QUERY,
-  LIGHT_CLIENT_TX_METHOD,
-  SIMPLE_COUNTER_QUERY,
-  SIMPLE_COUNTER_TX_METHOD,
-  TREASURY_QUERY,
-  TREASURY_TX_METHOD,
-} from "./contract.ts";
 import { SS58_FORMAT } from "./enum.ts";
 
 const toCamelCase = (str: string): string => {
@@ -47,6 +42,18 @@ const toPascalCase = (str: string): string => {
     .replace(new RegExp(/\w/), (s) => s.toUpperCase());
 };
 
+/** Gas limits used when the request does not give one. */
+export const DEFAULT_GAS_LIMIT = {
+  QUERY: 30000n * 1000000n,
+  CALL: 30000n * 1000000n,
+  DEPLOY: 100000n * 1000000n,
+};
+
+/** Parse the optional gas limit of a request. */
+export const parseGasLimit = (
+  gasLimit: number | string | undefined,
+): bigint | undefined => gasLimit === undefined ? undefined : BigInt(gasLimit);
+
 export const getPairFromSeed = (mnemonic: string): KeyringPair => {
   const seed: string = mnemonic;
   const keyring: Keyring = new Keyring({ type: "sr25519" });
@@ -54,6 +61,26 @@ export const getPairFromSeed = (mnemonic: string): KeyringPair => {
   return pair;
 };
 
+/** A mnemonic (or secret URI), or an encrypted keystore with its password. */
+export type Signer =
+  | string
+  | { mnemonic: string }
+  | { keystore: KeyringPair$Json; password: string };
+
+/** Return the unlocked pair of the signer given by a request. */
+export const getPairFromSigner = (
+  signer: Signer,
+  format?: SS58_FORMAT,
+): KeyringPair => {
+  const keyring: Keyring = new Keyring({ type: "sr25519" });
+  if (format !== undefined) keyring.setSS58Format(format);
+  if (typeof signer === "string") return keyring.addFromUri(signer);
+  if ("mnemonic" in signer) return keyring.addFromUri(signer.mnemonic);
+  const pair: KeyringPair = keyring.addFromJson(signer.keystore);
+  pair.unlock(signer.password);
+  return pair;
+};
+
 export const getPairFromSeedWithSS58 = (
   mnemonic: string,
   format: SS58_FORMAT,
@@ -91,36 +118,52 @@ export const getReservedBalance = async (
   return reservedBalance;
 };
 
-export const getTotalBalance = async (
+export type Balance = {
+  free: bigint;
+  reserved: bigint;
+};
+
+/** Return the free and reserved native token balance of the account, in planck. */
+export const getBalance = async (
   fullNodeUri: string,
   address: string,
-): Promise<bigint> => {
+): Promise<Balance> => {
   const provider: WsProvider = new WsProvider(fullNodeUri);
   const api: ApiPromise = await ApiPromise.create({ provider });
   const { data: balance } = await api.query.system.account(address);
-  const freeBalance = BigInt(balance?.free.toHuman().replace(/\,/g, "")); // 1 ROC = 1,000,000,000,000,000
-  const reservedBalance = BigInt(
-    balance?.reserved.toHuman().replace(/\,/g, ""),
-  ); // 1 ROC = 1,000,000,000,000,000, 1 SBY = 1,000,000,000,000,000,000
-  return freeBalance + reservedBalance;
-};
-
-export const getAbiFromContractName = async (name: string): Promise<string> => {
-  let path: ABI_PATH_OPTIONS;
-  switch (name) {
-    case CONTRACT.SIMPLE_COUNTER:
-      path = ABI_PATH.SIMPLE_COUNTER;
-      break;
-    case CONTRACT.LIGHT_CLIENT:
-      path = ABI_PATH.LIGHT_CLIENT;
-      break;
-    case CONTRACT.TREASURY:
-      path = ABI_PATH.TREASURY;
-      break;
-    default:
-      throw new Error("The contract name is invalid");
+  // 1 ROC = 1,000,000,000,000, 1 SBY = 1,000,000,000,000,000,000
+  return {
+    free: balance.free.toBigInt(),
+    reserved: balance.reserved.toBigInt(),
+  };
+};
+
+export const getTotalBalance = async (
+  fullNodeUri: string,
+  address: string,
+): Promise<bigint> => {
+  const { free, reserved } = await getBalance(fullNodeUri, address);
+  return free + reserved;
+};
+
+/**
+ * Return the metadata given with the request,
+ * or read `contracts/<name>.contract` if there is none.
+ */
+export const getAbiFromContractName = async (
+  name: string,
+  metadata?: AnyJson,
+): Promise<string> => {
+  if (metadata !== undefined && metadata !== null) {
+    return JSON.stringify(metadata);
+  }
+  if (!/^[A-Za-z0-9_-]+$/.test(name)) {
+    throw new Error("The contract name is invalid");
   }
-  return await fs.readFile(path, "utf8");
+  return await fs.readFile(
+    `./deno-http-server/contracts/${name}.contract`,
+    "utf8",
+  );
 };
 
 export const query = async (
@@ -128,86 +171,196 @@ export const query = async (
   contractName: string,
   contractAddr: string,
   field: string,
+  params: any[] = [],
+  gasLimit: bigint = DEFAULT_GAS_LIMIT.QUERY,
+  metadata?: AnyJson,
 ): Promise<AnyJson> => {
   const provider: WsProvider = new WsProvider(fullNodeUri);
   const api: ApiPromise = await ApiPromise.create({ provider });
-  const abi: string = await getAbiFromContractName(contractName);
+  const abi: string = await getAbiFromContractName(contractName, metadata);
   const PDAO_TEST_ADDR = "5CiTGDb8zaMMw6Sqrn8y3Awt9A6HiEdyf3wB7GrsbnpasVss";
-  const gasLimit: bigint = 30000n * 1000000n;
   const storageDepositLimit = null;
 
   const contract: ContractPromise = new ContractPromise(api, abi, contractAddr);
   let res: AnyJson;
-  let messageName: SIMPLE_COUNTER_QUERY | LIGHT_CLIENT_QUERY | TREASURY_QUERY;
-  switch (contractName) {
-    case CONTRACT.SIMPLE_COUNTER:
-      messageName = "get" + toPascalCase(field) as SIMPLE_COUNTER_QUERY;
-      break;
-    case CONTRACT.LIGHT_CLIENT:
-      messageName = "get" + toPascalCase(field) as LIGHT_CLIENT_QUERY;
-      break;
-    case CONTRACT.TREASURY:
-      messageName = "get" + toPascalCase(field) as TREASURY_QUERY;
-      break;
-    default:
-      throw new Error("contract name is invalid");
+  const messageName = "get" + toPascalCase(field);
+  if (contract.query[messageName] === undefined) {
+    throw new Error(`${contractName} has no message get_${field}`);
   }
   const { gasRequired, storageDeposit, result, output } = await contract
-    .query[messageName](PDAO_TEST_ADDR, { gasLimit, storageDepositLimit });
+    .query[messageName](
+      PDAO_TEST_ADDR,
+      { gasLimit, storageDepositLimit },
+      ...params,
+    );
   console.log("storageDeposit: ", storageDeposit.toHuman());
   console.log("gasRequire: ", gasRequired.toHuman());
   console.log("result: ", result.toHuman());
   console.log("output: ", output?.toHuman());
   if (output && output.toHuman()) {
-    res = output.toHuman();
+    // Unlike `toHuman`, numbers are not formatted with thousands separators.
+    res = output.toJSON();
   } else {
     throw new Error("output is invalid");
   }
   return res;
 };
 
+/** A SCALE encoded argument, with the display name of its type in the metadata. */
+export type TypedArgument = { type: string; value: string };
+
+/** An argument which polkadot.js encodes by itself, or a SCALE encoded one. */
+export type ContractArgument = string | number | boolean | TypedArgument;
+
+type MetadataArg = {
+  label: string;
+  type: { displayName: string[]; type: number };
+};
+
+/**
+ * Decode the SCALE encoded arguments of a message or a constructor,
+ * checking them against its arguments in the metadata.
+ * Plain arguments are passed as they are.
+ */
+export const decodeArguments = (
+  metadata: string,
+  { registry }: Abi,
+  kind: "messages" | "constructors",
+  label: string,
+  args: ContractArgument[],
+): unknown[] => {
+  const spec = JSON.parse(metadata).V3.spec[kind].find(
+    (x: { label: string }) => x.label === label,
+  );
+  if (spec === undefined) {
+    throw new Error(`${label} is not in the metadata`);
+  }
+  const specArgs: MetadataArg[] = spec.args;
+  if (specArgs.length !== args.length) {
+    throw new Error(
+      `${label} takes ${specArgs.length} arguments, got ${args.length}`,
+    );
+  }
+  return args.map((arg, i) => {
+    if (typeof arg !== "object" || arg === null) return arg;
+    const { label: name, type: { displayName, type } } = specArgs[i];
+    const expected = displayName[displayName.length - 1];
+    if (arg.type !== expected) {
+      throw new Error(`${name} of ${label} is ${expected}, got ${arg.type}`);
+    }
+    const bytes = hexToU8a(arg.value);
+    const decoded = registry.createTypeUnsafe(
+      registry.createLookupType(type),
+      [bytes],
+    );
+    if (decoded.encodedLength !== bytes.length) {
+      throw new Error(`${name} of ${label} is not a valid ${expected}`);
+    }
+    return decoded;
+  });
+};
+
+/** Thrown when the contract returns `Err`, with the reason given by the contract. */
+export class ContractRevertError extends Error {
+  reason: string;
+  constructor(reason: string) {
+    super(`contract reverted: ${reason}`);
+    this.name = "ContractRevertError";
+    this.reason = reason;
+  }
+}
+
+export type ContractEvent = {
+  name: string;
+  args: string[];
+};
+
+export type ContractTxResult = {
+  txHash: string;
+  blockNumber: number;
+  events: ContractEvent[];
+};
+
 export const sendContractTx = async (
   fullNodeUri: string,
-  mnemonic: string,
+  signer: Signer,
   contractName: string,
   contractAddr: string,
   methodName: string,
-  methodParams: any[],
-): Promise<string> => {
+  methodArgs: ContractArgument[],
+  gasLimit: bigint = DEFAULT_GAS_LIMIT.CALL,
+  metadata?: AnyJson,
+  storageDepositLimit: bigint | null = null,
+): Promise<ContractTxResult> => {
   const provider: WsProvider = new WsProvider(fullNodeUri);
   const api: ApiPromise = await ApiPromise.create({ provider });
-  const abi: string = await getAbiFromContractName(contractName);
-  const pair: KeyringPair = getPairFromSeed(mnemonic);
-  const gasLimit: bigint = 30000n * 1000000n;
-  const storageDepositLimit = null;
+  const abi: string = await getAbiFromContractName(contractName, metadata);
+  const pair: KeyringPair = getPairFromSigner(signer);
   const contract: ContractPromise = new ContractPromise(api, abi, contractAddr);
-  let messageName:
-    | SIMPLE_COUNTER_TX_METHOD
-    | LIGHT_CLIENT_TX_METHOD
-    | TREASURY_TX_METHOD;
-  switch (contractName) {
-    case CONTRACT.SIMPLE_COUNTER:
-      messageName = toCamelCase(methodName) as SIMPLE_COUNTER_TX_METHOD;
-      break;
-    case CONTRACT.LIGHT_CLIENT:
-      messageName = toCamelCase(methodName) as LIGHT_CLIENT_TX_METHOD;
-      break;
-    case CONTRACT.TREASURY:
-      messageName = toCamelCase(methodName) as TREASURY_TX_METHOD;
-      break;
-    default:
-      throw new Error("contract name is invalid");
+  const methodParams = decodeArguments(
+    abi,
+    contract.abi,
+    "messages",
+    methodName,
+    methodArgs,
+  );
+  const messageName = toCamelCase(methodName);
+  if (contract.tx[messageName] === undefined) {
+    throw new Error(`${contractName} has no message ${methodName}`);
+  }
+  // ink! messages returning `Err` do not revert, so dry-run the message first.
+  const { result: dryRunResult, output: dryRunOutput } = await contract
+    .query[messageName](
+      pair.address,
+      { gasLimit, storageDepositLimit },
+      ...methodParams,
+    );
+  if (dryRunResult.isErr) {
+    throw new Error(`contract trapped: ${dryRunResult.asErr.toString()}`);
+  }
+  const returned = dryRunOutput?.toJSON();
+  if (returned && typeof returned === "object" && "err" in returned) {
+    const { err } = returned as { err: AnyJson };
+    const reason = typeof err === "string" ? err : JSON.stringify(err);
+    throw new ContractRevertError(reason);
   }
   let _txHash: string | undefined = undefined;
+  let _events: ContractEvent[] = [];
+  let _blockNumber: number | undefined = undefined;
+  let _error: string | undefined = undefined;
   await contract.tx[messageName](
     { storageDepositLimit, gasLimit },
     ...methodParams,
   )
     .signAndSend(pair, (result) => {
+      trackSentTx(result.txHash.toHex(), result.status);
       if (result.status.isInBlock) {
         console.log("in a block");
-        const { txHash } = result;
-        _txHash = txHash.toString();
+        const { txHash, dispatchError, contractEvents } = result;
+        if (dispatchError?.isModule) {
+          const { section, name } = api.registry.findMetaError(
+            dispatchError.asModule,
+          );
+          _error = `dispatch error: ${section}.${name}`;
+        } else if (dispatchError) {
+          _error = `dispatch error: ${dispatchError.toString()}`;
+        } else {
+          _events = (contractEvents ?? []).map(({ event, args }) => ({
+            name: event.identifier,
+            args: args.map((arg) => {
+              const json = arg.toJSON();
+              return typeof json === "object"
+                ? JSON.stringify(json)
+                : String(json);
+            }),
+          }));
+          api.rpc.chain.getHeader(result.status.asInBlock).then(
+            ({ number }) => {
+              _blockNumber = number.toNumber();
+            },
+          );
+          _txHash = txHash.toString();
+        }
       } else if (result.status.isFinalized) {
         console.log("finalized");
       }
@@ -217,8 +370,11 @@ export const sendContractTx = async (
     const MAX_COUNT = 240;
     const timer = setInterval(() => {
       count++;
-      if (_txHash !== undefined) {
-        res(_txHash);
+      if (_error !== undefined) {
+        rej(new Error(_error));
+        clearInterval(timer);
+      } else if (_txHash !== undefined && _blockNumber !== undefined) {
+        res({ txHash: _txHash, blockNumber: _blockNumber, events: _events });
         clearInterval(timer);
       } else if (count > MAX_COUNT) {
         rej(new Error("Timeout: over 120 seconds"));
@@ -229,36 +385,46 @@ export const sendContractTx = async (
 
 export type contractDeploymentResult = {
   contractAddr: string;
+  codeHash: string;
   txHash: string;
+  blockNumber: number;
 };
 
 export const deployWithContractName = async (
   fullNodeUri: string,
-  mnemonic: string,
+  signer: Signer,
   contractName: string,
-  params: any[],
+  args: ContractArgument[],
+  gasLimit: bigint = DEFAULT_GAS_LIMIT.DEPLOY,
+  metadata?: AnyJson,
 ): Promise<contractDeploymentResult> => {
   const provider = new WsProvider(fullNodeUri);
   const api: ApiPromise = await ApiPromise.create({ provider: provider });
-  const abi: string = await getAbiFromContractName(contractName);
+  const abi: string = await getAbiFromContractName(contractName, metadata);
   const wasm: string = JSON.parse(abi).source.wasm;
   const code: CodePromise = new CodePromise(api, abi, wasm);
+  const params = decodeArguments(abi, code.abi, "constructors", "new", args);
 
-  const seed: string = mnemonic;
-  const keyring: Keyring = new Keyring({ type: "sr25519" });
-  const pair: KeyringPair = keyring.addFromUri(seed);
+  const pair: KeyringPair = getPairFromSigner(signer);
 
-  const gasLimit = 100000n * 1000000n;
   const storageDepositLimit: number | null = null;
   const tx: SubmittableExtrinsic<"promise", CodeSubmittableResult<ApiTypes>> =
     code.tx.new({ gasLimit, storageDepositLimit }, ...params);
+  const codeHash: string = JSON.parse(abi).source.hash;
   let address: string | undefined = undefined;
   let _txhash: string | undefined = undefined;
+  let blockNumber: number | undefined = undefined;
   const unsub = await tx.signAndSend(pair, ({ contract, status, txHash }) => {
     if (status.isInBlock || status.isFinalized) {
       address = contract?.address.toString();
       console.log("contract address : ", address);
       _txhash = txHash.toString();
+      const blockHash = status.isInBlock
+        ? status.asInBlock
+        : status.asFinalized;
+      api.rpc.chain.getHeader(blockHash).then(({ number }) => {
+        blockNumber = number.toNumber();
+      });
       unsub();
     }
   });
@@ -267,8 +433,11 @@ export const deployWithContractName = async (
     const MAX_COUNT = 120;
     const timer = setInterval(() => {
       count++;
-      if (address !== undefined && _txhash !== undefined) {
-        res({ contractAddr: address, txHash: _txhash });
+      if (
+        address !== undefined && _txhash !== undefined &&
+        blockNumber !== undefined
+      ) {
+        res({ contractAddr: address, codeHash, txHash: _txhash, blockNumber });
         clearInterval(timer);
       } else if (count > MAX_COUNT) {
         rej(new Error("Timeout: over 60 seconds"));
@@ -279,22 +448,30 @@ export const deployWithContractName = async (
 
 export const deployWithCodeHash = async (
   fullNodeUri: string,
-  mnemonic: string,
+  signer: Signer,
   contractName: string,
   salt: string | null,
-  params: any[],
+  args: ContractArgument[],
+  gasLimit: bigint = DEFAULT_GAS_LIMIT.DEPLOY,
+  metadata?: AnyJson,
+  codeHash?: string,
 ): Promise<contractDeploymentResult> => {
   const wsProvider = new WsProvider(fullNodeUri);
   const api: ApiPromise = await ApiPromise.create({ provider: wsProvider });
-  const abi: string = await getAbiFromContractName(contractName);
-  const { source: { hash } } = JSON.parse(abi);
+  const abi: string = await getAbiFromContractName(contractName, metadata);
+  // The code hash of the metadata, unless another build was uploaded.
+  const hash: string = codeHash || JSON.parse(abi).source.hash;
   const blueprint: BlueprintPromise = new BlueprintPromise(api, abi, hash);
+  const params = decodeArguments(
+    abi,
+    blueprint.abi,
+    "constructors",
+    "new",
+    args,
+  );
 
-  const seed: string = mnemonic;
-  const keyring: Keyring = new Keyring({ type: "sr25519" });
-  const pair: KeyringPair = keyring.addFromUri(seed);
+  const pair: KeyringPair = getPairFromSigner(signer);
 
-  const gasLimit = 100000n * 1000000n;
   const storageDepositLimit: number | null = null;
   const tx: SubmittableExtrinsic<
     "promise",
@@ -303,11 +480,18 @@ export const deployWithCodeHash = async (
 
   let address: string | undefined = undefined;
   let _txHash: string | undefined = undefined;
+  let blockNumber: number | undefined = undefined;
   const unsub = await tx.signAndSend(pair, ({ contract, status, txHash }) => {
     if (status.isInBlock || status.isFinalized) {
       address = contract?.address.toString();
       console.log("contract address : ", address);
       _txHash = txHash.toString();
+      const blockHash = status.isInBlock
+        ? status.asInBlock
+        : status.asFinalized;
+      api.rpc.chain.getHeader(blockHash).then(({ number }) => {
+        blockNumber = number.toNumber();
+      });
       unsub();
     }
   });
@@ -316,8 +500,16 @@ export const deployWithCodeHash = async (
     const MAX_COUNT = 120;
     const timer = setInterval(() => {
       count++;
-      if (address !== undefined && _txHash !== undefined) {
-        res({ contractAddr: address, txHash: _txHash });
+      if (
+        address !== undefined && _txHash !== undefined &&
+        blockNumber !== undefined
+      ) {
+        res({
+          contractAddr: address,
+          codeHash: hash,
+          txHash: _txHash,
+          blockNumber,
+        });
         clearInterval(timer);
       } else if (count > MAX_COUNT) {
         rej(new Error("Timeout: over 60 seconds"));
@@ -347,6 +539,42 @@ export const getBlockInfo = async (
   return blockInfo;
 };
 
+export type BlockSummary = {
+  blockHash: string;
+  blockNumber: number;
+  parentHash: string;
+  stateRoot: string;
+  extrinsicsRoot: string;
+  extrinsicCount: number;
+  /** Account ID of the validator which authored the block, in hex. */
+  author: string | null;
+};
+
+/** Return the summary of the block at the height or with the hash. */
+export const getBlockSummary = async (
+  fullNodeUri: string,
+  block: number | string,
+): Promise<BlockSummary> => {
+  const provider = new WsProvider(fullNodeUri);
+  const api: ApiPromise = await ApiPromise.create({ provider });
+  const blockHash = typeof block === "number"
+    ? await api.rpc.chain.getBlockHash(block)
+    : hexToU8a(block);
+  const [{ block: { header, extrinsics } }, { author }] = await Promise.all([
+    api.rpc.chain.getBlock(blockHash),
+    api.derive.chain.getHeader(blockHash),
+  ]);
+  return {
+    blockHash: header.hash.toHex(),
+    blockNumber: header.number.toNumber(),
+    parentHash: header.parentHash.toHex(),
+    stateRoot: header.stateRoot.toHex(),
+    extrinsicsRoot: header.extrinsicsRoot.toHex(),
+    extrinsicCount: extrinsics.length,
+    author: author ? author.toHex() : null,
+  };
+};
+
 export const getCurrentHeight = async (
   fullNodeUri: string,
 ): Promise<number> => {
@@ -356,16 +584,216 @@ export const getCurrentHeight = async (
   return parseInt(number.toString());
 };
 
+export type ChainInfo = {
+  chain: string;
+  genesisHash: string;
+};
+
+export const getChainInfo = async (fullNodeUri: string): Promise<ChainInfo> => {
+  const provider = new WsProvider(fullNodeUri);
+  const api: ApiPromise = await ApiPromise.create({ provider });
+  const chain = await api.rpc.system.chain();
+  return { chain: chain.toString(), genesisHash: api.genesisHash.toHex() };
+};
+
+export const contractExists = async (
+  fullNodeUri: string,
+  contractAddr: string,
+): Promise<boolean> => {
+  const provider = new WsProvider(fullNodeUri);
+  const api: ApiPromise = await ApiPromise.create({ provider });
+  const contractInfo = await api.query.contracts.contractInfoOf(contractAddr);
+  return contractInfo.isSome;
+};
+
 export const transferNativeToken = async (
   fullNodeUri: string,
-  mnemonic: string,
+  signer: Signer,
   to: string,
   amountInUnits: bigint,
 ): Promise<string> => {
   const provider = new WsProvider(fullNodeUri);
   const api: ApiPromise = await ApiPromise.create({ provider });
-  const pair: KeyringPair = getPairFromSeed(mnemonic);
+  const pair: KeyringPair = getPairFromSigner(signer);
   const transfer = api.tx.balances.transfer(to, amountInUnits);
-  const hash = await transfer.signAndSend(pair);
-  return hash.toHex();
+  // The hash is that of the signed extrinsic, which blocks include, only after signing.
+  await transfer.signAsync(pair);
+  const hash = transfer.hash.toHex();
+  const unsub = await transfer.send(({ txHash, status }) => {
+    trackSentTx(txHash.toHex(), status);
+    if (status.isFinalized) unsub();
+  });
+  return hash;
+};
+
+export type TxEvent = {
+  section: string;
+  method: string;
+  data: string[];
+};
+
+export type TxInclusion = {
+  blockHash: string;
+  blockNumber: number;
+  extrinsicIndex: number;
+  /** Why the runtime failed to dispatch the transaction, if it did. */
+  dispatchError: string | null;
+  /** Fee paid by the signer, in planck. */
+  fee: string;
+  events: TxEvent[];
+  finalized: boolean;
+};
+
+/** Status of a submitted extrinsic, as `signAndSend` streams it. */
+type SentTxStatus = {
+  isInBlock: boolean;
+  isFinalized: boolean;
+  asInBlock: { toHex(): string };
+  asFinalized: { toHex(): string };
+};
+
+/** Blocks including the transactions which this server sent, dropping the oldest ones. */
+const sentTxBlocks: Map<string, string> = new Map();
+const MAX_SENT_TXS = 1024;
+
+/** Remember the block which includes a transaction sent by this server. */
+export const trackSentTx = (txHash: string, status: SentTxStatus) => {
+  if (!status.isInBlock && !status.isFinalized) return;
+  const blockHash = status.isInBlock
+    ? status.asInBlock.toHex()
+    : status.asFinalized.toHex();
+  sentTxBlocks.delete(txHash);
+  sentTxBlocks.set(txHash, blockHash);
+  if (sentTxBlocks.size > MAX_SENT_TXS) {
+    sentTxBlocks.delete(sentTxBlocks.keys().next().value);
+  }
+};
+
+export type TxInclusionResult = {
+  inclusion: TxInclusion | null;
+  /** Height to look from in the next poll, since the blocks below are final or include it. */
+  resumeHeight: number;
+};
+
+/** Return how the block includes the transaction, or `null` if it does not. */
+const inclusionInBlock = async (
+  api: ApiPromise,
+  txHash: string,
+  blockHash: string,
+  height: number,
+  finalized: boolean,
+): Promise<TxInclusion | null> => {
+  const { block } = await api.rpc.chain.getBlock(blockHash);
+  const extrinsicIndex = block.extrinsics.findIndex(
+    (x) => x.hash.toHex() === txHash,
+  );
+  if (extrinsicIndex < 0) return null;
+
+  const signer = block.extrinsics[extrinsicIndex].signer.toString();
+  const records = await (await api.at(blockHash)).query.system.events();
+  let dispatchError: string | null = null;
+  let feePaid: string | undefined = undefined;
+  let withdrawn = 0n;
+  const events: TxEvent[] = [];
+  for (const { phase, event } of records) {
+    if (
+      !phase.isApplyExtrinsic || !phase.asApplyExtrinsic.eq(extrinsicIndex)
+    ) continue;
+    events.push({
+      section: event.section,
+      method: event.method,
+      data: event.data.map((x) => {
+        const json = x.toJSON();
+        return typeof json === "object" ? JSON.stringify(json) : String(json);
+      }),
+    });
+    if (api.events.system.ExtrinsicFailed.is(event)) {
+      const [error] = event.data;
+      if (error.isModule) {
+        const { section, name } = api.registry.findMetaError(error.asModule);
+        dispatchError = `${section}.${name}`;
+      } else {
+        dispatchError = error.toString();
+      }
+    } else if (
+      event.section === "transactionPayment" &&
+      event.method === "TransactionFeePaid"
+    ) {
+      feePaid = event.data[1].toString();
+    } else if (
+      api.events.balances.Withdraw.is(event) &&
+      event.data[0].toString() === signer
+    ) {
+      withdrawn += BigInt(event.data[1].toString());
+    }
+  }
+  return {
+    blockHash,
+    blockNumber: height,
+    extrinsicIndex,
+    dispatchError,
+    // Runtimes without `TransactionFeePaid` withdraw the fee from the signer.
+    fee: feePaid ?? withdrawn.toString(),
+    events,
+    finalized,
+  };
+};
+
+/**
+ * Look for the transaction in the block which `signAndSend` reported if this server sent it,
+ * or in the blocks from the given height to the best block otherwise.
+ */
+export const getTxInclusion = async (
+  fullNodeUri: string,
+  txHash: string,
+  fromHeight: number,
+): Promise<TxInclusionResult> => {
+  const provider = new WsProvider(fullNodeUri);
+  const api: ApiPromise = await ApiPromise.create({ provider });
+  try {
+    const best = (await api.rpc.chain.getHeader()).number.toNumber();
+    const finalizedHead = await api.rpc.chain.getFinalizedHead();
+    const finalized = (await api.rpc.chain.getHeader(finalizedHead)).number
+      .toNumber();
+
+    const sentBlock = sentTxBlocks.get(txHash);
+    if (sentBlock !== undefined) {
+      const height = (await api.rpc.chain.getHeader(sentBlock)).number
+        .toNumber();
+      // The block is skipped if a fork retracted it.
+      const canonical = (await api.rpc.chain.getBlockHash(height)).toHex();
+      if (canonical === sentBlock) {
+        const inclusion = await inclusionInBlock(
+          api,
+          txHash,
+          sentBlock,
+          height,
+          height <= finalized,
+        );
+        if (inclusion !== null) {
+          if (inclusion.finalized) sentTxBlocks.delete(txHash);
+          return { inclusion, resumeHeight: height };
+        }
+      }
+    }
+
+    for (let height = fromHeight; height <= best; height++) {
+      const blockHash = (await api.rpc.chain.getBlockHash(height)).toHex();
+      const inclusion = await inclusionInBlock(
+        api,
+        txHash,
+        blockHash,
+        height,
+        height <= finalized,
+      );
+      if (inclusion !== null) return { inclusion, resumeHeight: height };
+    }
+    // The blocks above the finalized one may still be retracted.
+    return {
+      inclusion: null,
+      resumeHeight: Math.max(fromHeight, Math.min(best, finalized) + 1),
+    };
+  } finally {
+    await api.disconnect();
+  }
 };
@@ -1,11 +1,19 @@
 import { Application, Router } from "https://deno.land/x/oak@v10.6.0/mod.ts";
 import {
   BlockInfo,
+  ChainInfo,
+  contractExists,
+  ContractRevertError,
   deployWithCodeHash,
   deployWithContractName,
+  getBalance,
   getBlockInfo,
+  getBlockSummary,
+  getChainInfo,
   getCurrentHeight,
-  getTotalBalance,
+  getPairFromSigner,
+  getTxInclusion,
+  parseGasLimit,
   query,
   sendContractTx,
   transferNativeToken,
@@ -65,20 +73,107 @@ router.post("/block-info", async (ctx) => {
   }
 });
 
+router.post("/block/summary", async (ctx) => {
+  try {
+    if (!ctx.request.hasBody) ctx.throw(415);
+    const reqBody = await ctx.request.body().value;
+    const block = await getBlockSummary(reqBody.fullNodeUri, reqBody.block);
+    ctx.response.body = {
+      success: true,
+      data: block,
+    };
+  } catch (err) {
+    ctx.response.body = {
+      success: false,
+      msg: err.toString(),
+    };
+  }
+});
+
+router.post("/tx/inclusion", async (ctx) => {
+  try {
+    if (!ctx.request.hasBody) ctx.throw(415);
+    const reqBody = await ctx.request.body().value;
+    const { inclusion, resumeHeight } = await getTxInclusion(
+      reqBody.fullNodeUri,
+      reqBody.txHash,
+      reqBody.fromHeight,
+    );
+    ctx.response.body = {
+      success: true,
+      data: {
+        inclusion,
+        resumeHeight,
+      },
+    };
+  } catch (err) {
+    ctx.response.body = {
+      success: false,
+      msg: err.toString(),
+    };
+  }
+});
+
+// complete
+router.post("/chain-info", async (ctx) => {
+  try {
+    if (!ctx.request.hasBody) ctx.throw(415);
+    const reqBody = await ctx.request.body().value;
+    const { chain, genesisHash }: ChainInfo = await getChainInfo(
+      reqBody.fullNodeUri,
+    );
+    ctx.response.body = {
+      success: true,
+      data: {
+        chain,
+        genesisHash,
+      },
+    };
+  } catch (err) {
+    ctx.response.body = {
+      success: false,
+      msg: err.toString(),
+    };
+  }
+});
+
+// complete
+router.post("/contract/exists", async (ctx) => {
+  try {
+    if (!ctx.request.hasBody) ctx.throw(415);
+    const reqBody = await ctx.request.body().value;
+    const exists: boolean = await contractExists(
+      reqBody.fullNodeUri,
+      reqBody.contractAddr,
+    );
+    ctx.response.body = {
+      success: true,
+      data: {
+        exists,
+      },
+    };
+  } catch (err) {
+    ctx.response.body = {
+      success: false,
+      msg: err.toString(),
+    };
+  }
+});
+
 // complete
 router.post("/account-info", async (ctx) => {
   try {
     if (!ctx.request.hasBody) ctx.throw(415);
     const reqBody = await ctx.request.body().value;
-    const totalBalance: bigint = await getTotalBalance(
+    const { free, reserved } = await getBalance(
       reqBody.fullNodeUri,
       reqBody.addr,
     );
-    const nativeToken = totalBalance.toString();
     ctx.response.body = {
       success: true,
       data: {
-        nativeToken,
+        nativeToken: (free + reserved).toString(),
+        freeNativeToken: free.toString(),
         memeToken: "0",
         nonFungibleToken: "0",
       },
@@ -91,6 +186,29 @@ router.post("/account-info", async (ctx) => {
   }
 });
 
+// complete
+router.post("/account/address", async (ctx) => {
+  try {
+    if (!ctx.request.hasBody) ctx.throw(415);
+    const reqBody = await ctx.request.body().value;
+    const { address } = getPairFromSigner(
+      reqBody.signer,
+      reqBody.ss58Format,
+    );
+    ctx.response.body = {
+      success: true,
+      data: {
+        address,
+      },
+    };
+  } catch (err) {
+    ctx.response.body = {
+      success: false,
+      msg: err.toString(),
+    };
+  }
+});
+
 router.post("/contract-state", async (ctx) => {
   try {
     if (!ctx.request.hasBody) ctx.throw(415);
@@ -100,13 +218,17 @@ router.post("/contract-state", async (ctx) => {
       reqBody.contractName,
       reqBody.contractAddr,
       reqBody.field,
+      [...(reqBody.arguments ?? [])],
+      parseGasLimit(reqBody.gasLimit),
+      reqBody.metadata,
     );
-    let output: string[] | string;
-    if (result?.toString()) {
-      output = result?.toString().split(",");
-    } else {
+    if (result === null || result === undefined) {
       throw new Error("query result error");
     }
+    // Each element of a list is an output, e.g. a list of accounts.
+    const output: string[] = (Array.isArray(result) ? result : [result]).map(
+      (x) => typeof x === "object" ? JSON.stringify(x) : String(x),
+    );
     ctx.response.body = {
       success: true,
       data: {
@@ -129,10 +251,10 @@ router.post("/native-token/transfer", async (ctx) => {
   try {
     if (!ctx.request.hasBody) ctx.throw(415);
     const reqBody = await ctx.request.body().value;
-    const amountInUnits: bigint = reqBody.amount;
+    const amountInUnits: bigint = BigInt(reqBody.amount);
     const txHash: string = await transferNativeToken(
       reqBody.fullNodeUri,
-      reqBody.mnemonic,
+      reqBody.signer,
       reqBody.toAddr,
       amountInUnits,
     );
@@ -157,13 +279,16 @@ router.post("/contract-method/execute", async (ctx) => {
     if (!ctx.request.hasBody) ctx.throw(415);
     const reqBody = await ctx.request.body().value;
     const params = [...reqBody.arguments];
-    const txHash: string = await sendContractTx(
+    const { txHash, blockNumber, events } = await sendContractTx(
       reqBody.fullNodeUri,
-      reqBody.mnemonic,
+      reqBody.signer,
       reqBody.contractName,
       reqBody.contractAddr,
       reqBody.methodName,
       params,
+      parseGasLimit(reqBody.gasLimit),
+      reqBody.metadata,
+      reqBody.storageDepositLimit ? BigInt(reqBody.storageDepositLimit) : null,
     );
     ctx.response.body = {
       success: true,
@@ -172,12 +297,15 @@ router.post("/contract-method/execute", async (ctx) => {
         messageName: reqBody.methodName,
         messageType: "tx",
         txHash,
+        blockNumber,
+        events,
       },
     };
   } catch (err) {
     ctx.response.body = {
       success: false,
       msg: err.toString(),
+      revert: err instanceof ContractRevertError ? err.reason : undefined,
     };
   }
 });
@@ -188,18 +316,23 @@ router.post("/contract/deploy", async (ctx) => {
     if (!ctx.request.hasBody) ctx.throw(415);
     const reqBody = await ctx.request.body().value;
     const params = [...reqBody.arguments];
-    const { contractAddr, txHash } = await deployWithContractName(
-      reqBody.fullNodeUri,
-      reqBody.mnemonic,
-      reqBody.contractName,
-      params,
-    );
+    const { contractAddr, codeHash, txHash, blockNumber } =
+      await deployWithContractName(
+        reqBody.fullNodeUri,
+        reqBody.signer,
+        reqBody.contractName,
+        params,
+        parseGasLimit(reqBody.gasLimit),
+        reqBody.metadata,
+      );
     ctx.response.body = {
       success: true,
       data: {
         contractName: reqBody.contractName,
         contractAddr,
+        codeHash,
         txHash,
+        blockNumber,
       },
     };
   } catch (err) {
@@ -219,19 +352,25 @@ router.post("/contract-from-code-hash/deploy", async (ctx) => {
     let salt: string | null;
     reqBody.salt === "null" ? salt = null : salt = reqBody.salt;
     if (reqBody.salt === null) salt = null;
-    const { contractAddr, txHash } = await deployWithCodeHash(
-      reqBody.fullNodeUri,
-      reqBody.mnemonic,
-      reqBody.contractName,
-      salt,
-      params,
-    );
+    const { contractAddr, codeHash, txHash, blockNumber } =
+      await deployWithCodeHash(
+        reqBody.fullNodeUri,
+        reqBody.signer,
+        reqBody.contractName,
+        salt,
+        params,
+        parseGasLimit(reqBody.gasLimit),
+        reqBody.metadata,
+        reqBody.codeHash,
+      );
     ctx.response.body = {
       success: true,
       data: {
         contractName: reqBody.contractName,
         contractAddr,
+        codeHash,
         txHash,
+        blockNumber,
       },
     };
   } catch (err) {
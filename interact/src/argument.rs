@@ -0,0 +1,107 @@
+use crate::*;
+use codec::{Decode, Encode};
+use std::fmt;
+use std::str::FromStr;
+
+/// A SCALE encoded argument of a contract message or constructor.
+///
+/// The http server checks it against the type of the argument in the metadata
+/// before sending the transaction.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Argument {
+    /// Display name of the type in the metadata, e.g. `u64`, `AccountId`, `Vec`.
+    #[serde(rename = "type")]
+    pub type_name: String,
+    /// SCALE encoded value in `0x` prefixed hex.
+    pub value: String,
+}
+
+impl Argument {
+    /// Encode a value of a type which the contract defines, e.g. a struct deriving `Encode`.
+    pub fn custom(type_name: &str, value: &impl Encode) -> Self {
+        Self {
+            type_name: type_name.to_owned(),
+            value: format!("0x{}", hex::encode(value.encode())),
+        }
+    }
+
+    /// Encode a value which implements `Serialize` but not `Encode`, as `scale` describes.
+    pub fn serialized(type_name: &str, value: &impl Serialize) -> Result<Self> {
+        Ok(Self {
+            type_name: type_name.to_owned(),
+            value: format!("0x{}", hex::encode(scale::to_vec(value)?)),
+        })
+    }
+
+    /// Return the SCALE encoded value.
+    pub fn encoded(&self) -> Result<Vec<u8>> {
+        decode_hex(&self.value)
+    }
+}
+
+impl<T: ArgumentType> From<T> for Argument {
+    fn from(value: T) -> Self {
+        Argument::custom(T::type_name(), &value)
+    }
+}
+
+/// A type which contract messages take, named as in the metadata.
+pub trait ArgumentType: Encode {
+    fn type_name() -> &'static str;
+}
+
+macro_rules! impl_argument_type {
+    ($($t:ty => $name:expr),* $(,)?) => {
+        $(impl ArgumentType for $t {
+            fn type_name() -> &'static str {
+                $name
+            }
+        })*
+    };
+}
+
+impl_argument_type!(
+    bool => "bool",
+    u8 => "u8",
+    u16 => "u16",
+    u32 => "u32",
+    u64 => "u64",
+    u128 => "u128",
+    i8 => "i8",
+    i16 => "i16",
+    i32 => "i32",
+    i64 => "i64",
+    i128 => "i128",
+    String => "String",
+    AccountId => "AccountId",
+);
+
+impl<T: ArgumentType> ArgumentType for Vec<T> {
+    fn type_name() -> &'static str {
+        "Vec"
+    }
+}
+
+impl<T: ArgumentType> ArgumentType for Option<T> {
+    fn type_name() -> &'static str {
+        "Option"
+    }
+}
+
+/// A 32 bytes account ID, given as an SS58 address of any format.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AccountId(pub [u8; 32]);
+
+impl FromStr for AccountId {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        rpc::decode_ss58(s).map(AccountId)
+    }
+}
+
+impl fmt::Debug for AccountId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "AccountId(0x{})", hex::encode(self.0))
+    }
+}
@@ -0,0 +1,77 @@
+//! Looking up blocks by height or hash, one at a time or over a range of heights.
+
+use crate::*;
+use futures::stream::{self, Stream, StreamExt};
+use serde_json::json;
+use std::ops::Range;
+
+/// A block, given by its height or its hash in `0x` prefixed hex.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum BlockId {
+    Height(u64),
+    Hash(String),
+}
+
+impl From<u64> for BlockId {
+    fn from(height: u64) -> Self {
+        BlockId::Height(height)
+    }
+}
+
+impl From<&str> for BlockId {
+    fn from(hash: &str) -> Self {
+        BlockId::Hash(hash.to_owned())
+    }
+}
+
+impl From<String> for BlockId {
+    fn from(hash: String) -> Self {
+        BlockId::Hash(hash)
+    }
+}
+
+/// The header of a block with the number of its extrinsics and its author.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockSummary {
+    pub block_hash: String,
+    pub block_number: u64,
+    pub parent_hash: String,
+    pub state_root: String,
+    pub extrinsics_root: String,
+    pub extrinsic_count: u32,
+    /// Account ID of the session validator which authored the block, in `0x` prefixed hex.
+    /// It is known only for blocks of Aura or BABE, e.g. none for the genesis block.
+    pub author: Option<String>,
+}
+
+impl InteractClient {
+    /// Return the summary of the block at the height or with the hash.
+    pub async fn get_block_summary(&self, block: impl Into<BlockId>) -> Result<BlockSummary> {
+        let block = block.into();
+        if self.config().read_backend == ReadBackend::JsonRpc {
+            return self.rpc().get_block_summary(&block).await;
+        }
+        let path = "block/summary";
+        let data = json!({
+            "fullNodeUri": self.config().full_node_uri,
+            "block": block,
+        });
+        let summary: BlockSummary = self.get_response(path, data).await?;
+
+        Ok(summary)
+    }
+
+    /// Stream the summaries of the blocks at the heights in order,
+    /// looking up at most `concurrency` blocks at a time.
+    pub fn block_summaries(
+        &self,
+        heights: Range<u64>,
+        concurrency: usize,
+    ) -> impl Stream<Item = Result<BlockSummary>> + '_ {
+        stream::iter(heights)
+            .map(move |height| self.get_block_summary(height))
+            .buffered(concurrency.max(1))
+    }
+}
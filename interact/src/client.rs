@@ -0,0 +1,555 @@
+use crate::*;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+/// Gas limits given to the contract calls, in weight.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GasLimits {
+    pub query: u64,
+    pub call: u64,
+    pub deploy: u64,
+}
+
+impl Default for GasLimits {
+    fn default() -> Self {
+        Self {
+            query: 30_000_000_000,
+            call: 30_000_000_000,
+            deploy: 100_000_000_000,
+        }
+    }
+}
+
+/// Configuration of an `InteractClient`.
+#[derive(Debug, Clone)]
+pub struct ClientConfig {
+    /// Websocket URI of the full node.
+    pub full_node_uri: String,
+    /// URL of the http server, ending with `/`.
+    pub http_server: String,
+    /// Timeout of each transaction sent through the http server,
+    /// which waits for the transaction to be included in a block.
+    pub timeout: Duration,
+    /// Timeout of each read from the http server or the full node.
+    pub read_timeout: Duration,
+    /// Timeout of connecting to the http server.
+    pub connect_timeout: Duration,
+    pub gas_limits: GasLimits,
+    /// Which backend reads the height, the blocks and the accounts.
+    pub read_backend: ReadBackend,
+    /// Decimal places of the native token in planck.
+    /// e.g. 18 for Shibuya, 12 for Rococo, 10 for Polkadot.
+    pub decimals: u32,
+    /// How the failed requests are retried.
+    pub retry: RetryPolicy,
+    /// Deployment registry which every deployment of the client is appended to, if any.
+    pub registry: Option<PathBuf>,
+}
+
+impl ClientConfig {
+    pub fn new(full_node_uri: &str, http_server: &str) -> Self {
+        Self {
+            full_node_uri: full_node_uri.to_owned(),
+            http_server: http_server.to_owned(),
+            timeout: Duration::from_secs(180),
+            read_timeout: Duration::from_secs(30),
+            connect_timeout: Duration::from_secs(10),
+            gas_limits: GasLimits::default(),
+            read_backend: ReadBackend::default(),
+            decimals: 18,
+            retry: RetryPolicy::default(),
+            registry: None,
+        }
+    }
+}
+
+/// A client of the http server, which reuses its connections across the calls.
+#[derive(Debug, Clone)]
+pub struct InteractClient {
+    config: ClientConfig,
+    http: reqwest::Client,
+    rpc: Arc<RpcClient>,
+    /// Metadata to check the calls against, by the name of the contract.
+    metadata: HashMap<String, Arc<ContractMetadata>>,
+    /// Metadata files which the contracts carry, by their path, read once.
+    metadata_files: Arc<Mutex<HashMap<PathBuf, Arc<MetadataFile>>>>,
+    /// Held while the deployment registry is read and written back.
+    registry_lock: Arc<Mutex<()>>,
+}
+
+/// A metadata file which a contract carries.
+#[derive(Debug)]
+struct MetadataFile {
+    metadata: Arc<ContractMetadata>,
+    /// The content of the file without the Wasm blob, which only deployments need.
+    abi: Value,
+    wasm: Option<Value>,
+}
+
+impl InteractClient {
+    pub fn new(config: ClientConfig) -> Result<Self> {
+        let http = reqwest::Client::builder()
+            .timeout(config.timeout)
+            .connect_timeout(config.connect_timeout)
+            .build()?;
+        let rpc = Arc::new(
+            RpcClient::new(&config.full_node_uri, config.read_timeout)
+                .with_retry(config.retry.clone()),
+        );
+        Ok(Self {
+            config,
+            http,
+            rpc,
+            metadata: HashMap::new(),
+            metadata_files: Arc::new(Mutex::new(HashMap::new())),
+            registry_lock: Arc::new(Mutex::new(())),
+        })
+    }
+
+    /// Check the calls to the contract of the name against its metadata before sending them.
+    /// Contracts which carry their own metadata are checked against it instead.
+    pub fn with_metadata(mut self, name: &str, metadata: ContractMetadata) -> Self {
+        self.metadata.insert(name.to_owned(), Arc::new(metadata));
+        self
+    }
+
+    /// Return the metadata to check the calls to the contract against,
+    /// and the metadata to give to the http server if the contract carries its own,
+    /// with the Wasm blob only if it is to be deployed.
+    pub(crate) fn metadata_of(
+        &self,
+        contract: &Contract,
+        with_wasm: bool,
+    ) -> Result<(Option<Arc<ContractMetadata>>, Option<Value>)> {
+        match &contract.metadata {
+            Some(path) => {
+                let file = self.metadata_file(path)?;
+                let mut bundled = file.abi.clone();
+                if let (true, Some(wasm)) = (with_wasm, &file.wasm) {
+                    bundled["source"]["wasm"] = wasm.clone();
+                }
+                Ok((Some(Arc::clone(&file.metadata)), Some(bundled)))
+            }
+            None => Ok((self.metadata.get(&contract.name).cloned(), None)),
+        }
+    }
+
+    /// Read and parse the metadata file, unless it was read before.
+    fn metadata_file(&self, path: &Path) -> Result<Arc<MetadataFile>> {
+        if let Some(file) = self.metadata_files().get(path) {
+            return Ok(Arc::clone(file));
+        }
+        let content = std::fs::read_to_string(path)?;
+        let metadata: ContractMetadata = content.parse()?;
+        let mut abi: Value = serde_json::from_str(&content)?;
+        let wasm = abi
+            .get_mut("source")
+            .and_then(|x| x.as_object_mut())
+            .and_then(|x| x.remove("wasm"));
+        let file = Arc::new(MetadataFile {
+            metadata: Arc::new(metadata),
+            abi,
+            wasm,
+        });
+        self.metadata_files()
+            .insert(path.to_owned(), Arc::clone(&file));
+        Ok(file)
+    }
+
+    fn metadata_files(&self) -> MutexGuard<'_, HashMap<PathBuf, Arc<MetadataFile>>> {
+        self.metadata_files
+            .lock()
+            .expect("metadata files are poisoned")
+    }
+
+    pub fn config(&self) -> &ClientConfig {
+        &self.config
+    }
+
+    /// Return the client of the JSON-RPC API of the full node.
+    pub fn rpc(&self) -> &RpcClient {
+        &self.rpc
+    }
+
+    /// Send data to the path and return the data of the response.
+    /// The request must not change the chain, since it is retried under the retry policy.
+    pub async fn get_response<T: DeserializeOwned>(&self, path: &str, data: Value) -> Result<T> {
+        self.config
+            .retry
+            .run(true, || self.post(path, &data, self.config.read_timeout))
+            .await
+    }
+
+    /// Send a transaction to the path and return the data of the response.
+    /// It is retried only if the http server could not be reached, so it is never sent twice.
+    pub async fn send_transaction<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        data: Value,
+    ) -> Result<T> {
+        self.config
+            .retry
+            .run(false, || self.post(path, &data, self.config.timeout))
+            .await
+    }
+
+    async fn post<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        data: &Value,
+        timeout: Duration,
+    ) -> Result<T> {
+        let response = self
+            .http
+            .post(self.config.http_server.clone() + path)
+            .timeout(timeout)
+            .json(data)
+            .send()
+            .await?;
+
+        let result: Response<T> = match response.status() {
+            reqwest::StatusCode::OK => serde_json::from_slice(&response.bytes().await?)?,
+            other => return Err(Error::HttpStatus(other)),
+        };
+
+        result.into_result()
+    }
+
+    /// Check that the http server is up.
+    pub async fn check_http_server(&self) -> Result<()> {
+        self.config
+            .retry
+            .run(true, || async {
+                let response = self
+                    .http
+                    .get(&self.config.http_server)
+                    .timeout(self.config.read_timeout)
+                    .send()
+                    .await?;
+                if response.status() != reqwest::StatusCode::OK {
+                    return Err(Error::HttpStatus(response.status()));
+                }
+                Ok(())
+            })
+            .await
+    }
+
+    /// Return the name and the genesis hash of the chain.
+    pub async fn get_chain_info(&self) -> Result<ChainInfo> {
+        let path = "chain-info";
+        let data = json!({
+            "fullNodeUri": self.config.full_node_uri,
+        });
+        let chain_info: ChainInfo = self.get_response(path, data).await?;
+
+        Ok(chain_info)
+    }
+
+    /// Return the current block height.
+    pub async fn get_current_height(&self) -> Result<u64> {
+        if self.config.read_backend == ReadBackend::JsonRpc {
+            return self.rpc.get_current_height().await;
+        }
+        let path = "current-height";
+        let data = json!({
+            "fullNodeUri": self.config.full_node_uri,
+        });
+        let Height { height } = self.get_response(path, data).await?;
+
+        Ok(height)
+    }
+
+    /// Return the block hash and timestamp of the block at the height.
+    pub async fn get_block(&self, height: u64) -> Result<Block> {
+        if self.config.read_backend == ReadBackend::JsonRpc {
+            return self.rpc.get_block(height).await;
+        }
+        let path = "block-info";
+        let data = json!({
+            "fullNodeUri": self.config.full_node_uri,
+            "height": height,
+        });
+        let block: Block = self.get_response(path, data).await?;
+
+        Ok(block)
+    }
+
+    /// Return the native token, meme token(TBD), nft(TBD) balance of the given account.
+    pub async fn query_account(&self, addr: &str) -> Result<Account> {
+        if self.config.read_backend == ReadBackend::JsonRpc {
+            return self.rpc.query_account(addr).await;
+        }
+        let path = "account-info";
+        let data = json!({
+            "fullNodeUri": self.config.full_node_uri,
+            "addr": addr,
+        });
+        let account: Account = self.get_response(path, data).await?;
+
+        Ok(account)
+    }
+
+    /// Return the SS58 address of the signer.
+    /// e.g. 5 for Astar family, 42 for generic substrate chains.
+    pub async fn get_address(&self, signer: &dyn Signer, ss58_format: u16) -> Result<String> {
+        let path = "account/address";
+        let data = json!({
+            "signer": signer.credential()?,
+            "ss58Format": ss58_format,
+        });
+        let Address { address } = self.get_response(path, data).await?;
+
+        Ok(address)
+    }
+
+    /// Return the planck in one native token.
+    pub fn planck_to_one(&self) -> u128 {
+        10u128.pow(self.config.decimals)
+    }
+
+    /// Track the transaction, looking for it from the given height.
+    pub fn track(&self, tx_hash: &str, from_height: u64) -> TxHandle {
+        TxHandle::new(self.clone(), tx_hash, from_height)
+    }
+
+    /// Transfer the native token in planck to receiver account.
+    /// It returns as soon as the transaction is submitted, with a handle to wait for it.
+    pub async fn transfer_native_token(
+        &self,
+        signer: &dyn Signer,
+        receiver_public_key: &str,
+        amount: u128,
+    ) -> Result<TxHandle> {
+        let path = "native-token/transfer";
+        let from_height = self.get_current_height().await?;
+        let data = json!({
+            "fullNodeUri": self.config.full_node_uri,
+            "signer": signer.credential()?,
+            "toAddr": receiver_public_key,
+            // Planck may exceed the safe integers of JSON.
+            "amount": amount.to_string(),
+        });
+        let TxHash { tx_hash } = self.send_transaction(path, data).await?;
+
+        Ok(self.track(&tx_hash, from_height))
+    }
+
+    /// Query the state of the deployed contract.
+    /// The getter of the field may take arguments, e.g. the token to get the balance of.
+    pub async fn query_contract_state(
+        &self,
+        contract: &Contract,
+        field: &str,
+        arguments: Vec<&str>,
+    ) -> Result<ContractQuery> {
+        let path = "contract-state";
+        let (metadata, bundled) = self.metadata_of(contract, false)?;
+        if let Some(metadata) = metadata {
+            let message = metadata.message(&format!("get_{}", field))?;
+            if message.args.len() != arguments.len() {
+                return Err(Error::InvalidCall(format!(
+                    "{} takes {} arguments, got {}",
+                    message.label,
+                    message.args.len(),
+                    arguments.len()
+                )));
+            }
+        }
+        let data = json!({
+            "fullNodeUri": self.config.full_node_uri,
+            "contractAddr": contract.address()?,
+            "contractName": contract.name,
+            "metadata": bundled,
+            "field": field,
+            "arguments": arguments,
+            "gasLimit": self.config.gas_limits.query,
+        });
+        let contract_query: ContractQuery = self.get_response(path, data).await?;
+
+        Ok(contract_query)
+    }
+
+    /// Return whether a contract is instantiated at the address.
+    pub async fn contract_exists(&self, contract_addr: &str) -> Result<bool> {
+        let path = "contract/exists";
+        let data = json!({
+            "fullNodeUri": self.config.full_node_uri,
+            "contractAddr": contract_addr,
+        });
+        let Exists { exists } = self.get_response(path, data).await?;
+
+        Ok(exists)
+    }
+
+    /// Execute the method(Send a transaction) of the deployed contract.
+    /// It returns after the transaction is included in a block, or fails if the contract rejects it.
+    /// Track it with `track(&tx.tx_hash, tx.block_number)` to wait for its finality.
+    /// The http server rejects the arguments which do not match the metadata of the contract.
+    pub async fn execute_contract_method(
+        &self,
+        signer: &dyn Signer,
+        contract: &Contract,
+        method_name: &str,
+        arguments: Vec<Argument>,
+    ) -> Result<ContractTx> {
+        self.execute_contract_method_with_limits(
+            signer,
+            contract,
+            method_name,
+            arguments,
+            self.config.gas_limits.call,
+            None,
+        )
+        .await
+    }
+
+    /// Execute the method with the gas limit and the storage deposit limit in planck,
+    /// e.g. those estimated by `dry_run_call`. No storage deposit limit means any.
+    pub async fn execute_contract_method_with_limits(
+        &self,
+        signer: &dyn Signer,
+        contract: &Contract,
+        method_name: &str,
+        arguments: Vec<Argument>,
+        gas_limit: u64,
+        storage_deposit_limit: Option<u128>,
+    ) -> Result<ContractTx> {
+        let path = "contract-method/execute";
+        let (metadata, bundled) = self.metadata_of(contract, false)?;
+        if let Some(metadata) = metadata {
+            metadata.validate_message(method_name, &arguments)?;
+        }
+        let data = json!({
+            "fullNodeUri": self.config.full_node_uri,
+            "signer": signer.credential()?,
+            "contractAddr": contract.address()?,
+            "contractName": contract.name,
+            "metadata": bundled,
+            "methodName": method_name,
+            "arguments": arguments,
+            "gasLimit": gas_limit,
+            // Planck may exceed the safe integers of JSON.
+            "storageDepositLimit": storage_deposit_limit.map(|x| x.to_string()),
+        });
+        let contract_tx: ContractTx = self.send_transaction(path, data).await?;
+
+        Ok(contract_tx)
+    }
+
+    /// Deploy the contract with the Wasm blob of its metadata.
+    /// e.g. simple_counter, light_client, treasury.
+    /// The deployment is appended to the registry of the config, if any.
+    pub async fn deploy_contract(
+        &self,
+        signer: &dyn Signer,
+        contract: &Contract,
+        arguments: Vec<Argument>,
+    ) -> Result<ContractDeploy> {
+        let path = "contract/deploy";
+        let (metadata, bundled) = self.metadata_of(contract, true)?;
+        if let Some(metadata) = metadata {
+            metadata.validate_constructor("new", &arguments)?;
+        }
+        let data = json!({
+            "fullNodeUri": self.config.full_node_uri,
+            "signer": signer.credential()?,
+            "contractName": contract.name,
+            "metadata": bundled,
+            "arguments": arguments,
+            "gasLimit": self.config.gas_limits.deploy,
+        });
+        let contract_deploy: ContractDeploy = self.send_transaction(path, data).await?;
+        self.record_deployment(contract, &contract_deploy)?;
+
+        Ok(contract_deploy)
+    }
+
+    /// Deploy the contract from the code hash of the contract, or of its metadata if it has none.
+    /// The deployment is appended to the registry of the config, if any.
+    pub async fn deploy_contract_with_code_hash(
+        &self,
+        signer: &dyn Signer,
+        contract: &Contract,
+        arguments: Vec<Argument>,
+        salt: &str,
+    ) -> Result<ContractDeploy> {
+        let path = "contract-from-code-hash/deploy";
+        let (metadata, bundled) = self.metadata_of(contract, false)?;
+        if let Some(metadata) = metadata {
+            metadata.validate_constructor("new", &arguments)?;
+        }
+        let data = json!({
+            "fullNodeUri": self.config.full_node_uri,
+            "signer": signer.credential()?,
+            "contractName": contract.name,
+            "metadata": bundled,
+            "codeHash": contract.code_hash,
+            "arguments": arguments,
+            "salt": salt,
+            "gasLimit": self.config.gas_limits.deploy,
+        });
+        let contract_deploy: ContractDeploy = self.send_transaction(path, data).await?;
+        self.record_deployment(contract, &contract_deploy)?;
+
+        Ok(contract_deploy)
+    }
+
+    /// Append the deployment to the registry of the config, if any.
+    fn record_deployment(&self, contract: &Contract, deploy: &ContractDeploy) -> Result<()> {
+        let path = match &self.config.registry {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+        let _lock = self
+            .registry_lock
+            .lock()
+            .expect("the registry lock is poisoned");
+        registry::DeploymentRegistry::open(path)?
+            .append(registry::Deployment::new(contract.clone(), deploy))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_metadata_files_once() {
+        let path = std::env::temp_dir().join(format!(
+            "pdao-interact-{}-read_metadata_files_once.contract",
+            std::process::id()
+        ));
+        std::fs::copy(
+            concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/../deno-http-server/contracts/simple_counter.contract"
+            ),
+            &path,
+        )
+        .unwrap();
+        let client =
+            InteractClient::new(ClientConfig::new("ws://127.0.0.1:1", "http://127.0.0.1:9/"))
+                .unwrap();
+        let counter = Contract::new(Contract::SIMPLE_COUNTER).with_metadata(&path);
+
+        let (metadata, bundled) = client.metadata_of(&counter, false).unwrap();
+        let bundled = bundled.unwrap();
+        assert!(metadata.unwrap().wasm().is_ok());
+        assert!(bundled["source"].get("wasm").is_none());
+        assert!(bundled["source"]["hash"].is_string());
+        let (_, bundled) = client.metadata_of(&counter, true).unwrap();
+        assert!(bundled.unwrap()["source"]["wasm"].is_string());
+
+        // The file is not read again, by the clones of the client too.
+        std::fs::remove_file(&path).unwrap();
+        assert!(client.clone().metadata_of(&counter, false).is_ok());
+        assert!(InteractClient::new(client.config().clone())
+            .unwrap()
+            .metadata_of(&counter, false)
+            .is_err());
+    }
+}
@@ -0,0 +1,136 @@
+use crate::*;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// A contract which the http server drives, identified by its name and optionally
+/// by its address, its own metadata and the code hash to instantiate it from.
+///
+/// It is written as `<name>[@<address>][?metadata=<path>][&code_hash=<hash>]`
+/// in config files and on the command line, e.g. `simple_counter@5Grw...utQY`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(try_from = "String", into = "String")]
+pub struct Contract {
+    /// Name of the contract, e.g. `simple_counter`.
+    /// The http server reads `contracts/<name>.contract` unless `metadata` is given.
+    pub name: String,
+    /// SS58 address of the instance, which calls and queries need.
+    pub address: Option<String>,
+    /// `.contract` bundle or `.json` metadata file given to the http server with each request.
+    pub metadata: Option<PathBuf>,
+    /// Code hash in `0x` prefixed hex to instantiate from, instead of that of the metadata.
+    pub code_hash: Option<String>,
+}
+
+impl Contract {
+    pub const SIMPLE_COUNTER: &'static str = "simple_counter";
+    pub const TREASURY: &'static str = "treasury";
+    pub const LIGHT_CLIENT: &'static str = "light_client";
+
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            address: None,
+            metadata: None,
+            code_hash: None,
+        }
+    }
+
+    /// Return the same contract, instantiated at the address.
+    pub fn at(mut self, address: &str) -> Self {
+        self.address = Some(address.to_owned());
+        self
+    }
+
+    pub fn with_metadata(mut self, path: impl AsRef<Path>) -> Self {
+        self.metadata = Some(path.as_ref().to_owned());
+        self
+    }
+
+    pub fn with_code_hash(mut self, code_hash: &str) -> Self {
+        self.code_hash = Some(code_hash.to_owned());
+        self
+    }
+
+    /// Return the address of the instance, which every call to it needs.
+    pub fn address(&self) -> Result<&str> {
+        self.address
+            .as_deref()
+            .ok_or_else(|| Error::InvalidContract(format!("{} has no address", self.name)))
+    }
+}
+
+impl FromStr for Contract {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = |reason: &str| Error::InvalidContract(format!("{}: {}", s, reason));
+        let (identity, options) = match s.split_once('?') {
+            Some((identity, options)) => (identity, Some(options)),
+            None => (s, None),
+        };
+        let (name, address) = match identity.split_once('@') {
+            Some((name, address)) => (name, Some(address)),
+            None => (identity, None),
+        };
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|x| x.is_ascii_alphanumeric() || x == '_' || x == '-')
+        {
+            return Err(invalid("invalid name"));
+        }
+        let mut contract = Contract::new(name);
+        if let Some(address) = address {
+            if address.is_empty() {
+                return Err(invalid("empty address"));
+            }
+            contract = contract.at(address);
+        }
+
+        for option in options.into_iter().flat_map(|x| x.split('&')) {
+            match option.split_once('=') {
+                Some(("metadata", path)) if !path.is_empty() => {
+                    contract = contract.with_metadata(path)
+                }
+                Some(("code_hash", code_hash)) if !code_hash.is_empty() => {
+                    contract = contract.with_code_hash(code_hash)
+                }
+                _ => return Err(invalid(&format!("unknown option {}", option))),
+            }
+        }
+        Ok(contract)
+    }
+}
+
+impl fmt::Display for Contract {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.name)?;
+        if let Some(address) = &self.address {
+            write!(f, "@{}", address)?;
+        }
+        let mut separator = '?';
+        if let Some(metadata) = &self.metadata {
+            write!(f, "{}metadata={}", separator, metadata.display())?;
+            separator = '&';
+        }
+        if let Some(code_hash) = &self.code_hash {
+            write!(f, "{}code_hash={}", separator, code_hash)?;
+        }
+        Ok(())
+    }
+}
+
+impl TryFrom<String> for Contract {
+    type Error = Error;
+
+    fn try_from(value: String) -> Result<Self> {
+        value.parse()
+    }
+}
+
+impl From<Contract> for String {
+    fn from(contract: Contract) -> Self {
+        contract.to_string()
+    }
+}
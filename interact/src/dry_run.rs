@@ -0,0 +1,304 @@
+//! Simulating contract calls and instantiations on the full node, without submitting them.
+
+use crate::*;
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+/// Flag of the output of a contract which reverted the state.
+pub(crate) const FLAG_REVERT: u32 = 1;
+
+/// SS58 format of generic Substrate chains, which every node accepts as an origin.
+const GENERIC_SS58_FORMAT: u16 = 42;
+
+/// The storage deposit which a call charges the caller or refunds to it, in planck.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageDeposit {
+    Charge(u128),
+    Refund(u128),
+}
+
+/// What a simulated call or instantiation would do.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DryRun {
+    pub gas_consumed: u64,
+    /// Gas limit which the call needs, which may be above the gas consumed.
+    pub gas_required: u64,
+    pub storage_deposit: StorageDeposit,
+    /// Messages of `ink_env::debug_println!`, if the node is run with them.
+    pub debug_message: String,
+    /// The decoded return value of the message, or `None` if it returns nothing or trapped.
+    pub return_value: Option<Value>,
+    /// Why the contract reverted, e.g. `ValueIsOver10` of a message returning `Err`.
+    pub revert: Option<String>,
+    /// Why the runtime failed to run the contract, e.g. it trapped or ran out of gas.
+    pub dispatch_error: Option<String>,
+    /// Address of the instantiated contract.
+    pub contract_addr: Option<String>,
+}
+
+impl DryRun {
+    /// Return whether the call would succeed.
+    pub fn is_ok(&self) -> bool {
+        self.revert.is_none() && self.dispatch_error.is_none()
+    }
+
+    /// Return the error which the submission would fail with, if any.
+    pub fn check(&self) -> Result<()> {
+        if let Some(reason) = &self.revert {
+            return Err(Error::ContractReverted(reason.clone()));
+        }
+        if let Some(e) = &self.dispatch_error {
+            return Err(Error::ContractReverted(format!("dispatch error: {}", e)));
+        }
+        Ok(())
+    }
+
+    /// Return the deposit limit to submit with, which is none if the call refunds.
+    pub fn storage_deposit_limit(&self) -> Option<u128> {
+        match self.storage_deposit {
+            StorageDeposit::Charge(charge) => Some(charge),
+            StorageDeposit::Refund(_) => None,
+        }
+    }
+}
+
+/// `ContractResult` of `pallet_contracts`.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ContractResult {
+    gas_consumed: Value,
+    gas_required: Value,
+    storage_deposit: Value,
+    #[serde(default)]
+    debug_message: Option<String>,
+    pub(crate) result: Value,
+}
+
+impl InteractClient {
+    /// Simulate sending the message from the origin, an SS58 address, with the value in planck.
+    /// The contract must carry its metadata or be registered with `with_metadata`.
+    pub async fn dry_run_call(
+        &self,
+        origin: &str,
+        contract: &Contract,
+        method_name: &str,
+        arguments: &[Argument],
+        value: u128,
+    ) -> Result<DryRun> {
+        let metadata = self.require_metadata(contract)?;
+        let message = metadata.validate_message(method_name, arguments)?;
+        let input_data = input_data(message.selector()?, arguments)?;
+        let result = self
+            .contracts_call(origin, contract.address()?, &input_data, value)
+            .await?;
+
+        let output = result.result.get("Ok").or_else(|| result.result.get("ok"));
+        let mut dry_run = dry_run(&result, output.is_none())?;
+        if let Some(output) = output {
+            let (flags, data) = exec_output(output)?;
+            let returned = match &message.return_type {
+                Some(ty) => Some(metadata.decode_value(ty.id, &mut data.as_slice())?),
+                None => None,
+            };
+            dry_run.revert = revert_reason(returned.as_ref(), flags);
+            dry_run.return_value = returned;
+        }
+        Ok(dry_run)
+    }
+
+    /// Simulate instantiating the contract from the origin with the value in planck,
+    /// from the code hash of the contract if it has one, or uploading its Wasm blob otherwise.
+    pub async fn dry_run_instantiate(
+        &self,
+        origin: &str,
+        contract: &Contract,
+        arguments: &[Argument],
+        value: u128,
+        salt: &[u8],
+    ) -> Result<DryRun> {
+        let metadata = self.require_metadata(contract)?;
+        let constructor = metadata.validate_constructor("new", arguments)?;
+        let code = match &contract.code_hash {
+            Some(code_hash) => json!({ "existing": code_hash }),
+            None => json!({ "upload": format!("0x{}", hex::encode(metadata.wasm()?)) }),
+        };
+        let request = json!({
+            "origin": origin,
+            // Values above `u64::MAX` are not JSON numbers of the node.
+            "value": format!("0x{:x}", value),
+            "gasLimit": self.config().gas_limits.deploy,
+            "storageDepositLimit": null,
+            "code": code,
+            "data": input_data(constructor.selector()?, arguments)?,
+            "salt": format!("0x{}", hex::encode(salt)),
+        });
+        let result: ContractResult = self
+            .rpc()
+            .request("contracts_instantiate", json!([request]))
+            .await?;
+
+        let output = result.result.get("Ok").or_else(|| result.result.get("ok"));
+        let mut dry_run = dry_run(&result, output.is_none())?;
+        if let Some(output) = output {
+            let (flags, _) = exec_output(&output["result"])?;
+            dry_run.revert = revert_reason(None, flags);
+            dry_run.contract_addr = output["accountId"].as_str().map(|x| x.to_owned());
+        }
+        Ok(dry_run)
+    }
+
+    /// Dry-run the message from the signer, and send it only if it would succeed,
+    /// with the gas and the storage deposit which the dry-run estimated.
+    pub async fn execute_contract_method_estimated(
+        &self,
+        signer: &dyn Signer,
+        contract: &Contract,
+        method_name: &str,
+        arguments: Vec<Argument>,
+    ) -> Result<ContractTx> {
+        let origin = self.get_address(signer, GENERIC_SS58_FORMAT).await?;
+        let dry_run = self
+            .dry_run_call(&origin, contract, method_name, &arguments, 0)
+            .await?;
+        dry_run.check()?;
+        self.execute_contract_method_with_limits(
+            signer,
+            contract,
+            method_name,
+            arguments,
+            dry_run.gas_required,
+            dry_run.storage_deposit_limit(),
+        )
+        .await
+    }
+
+    /// Send `contracts_call` of the input data in `0x` prefixed hex to the contract at `dest`.
+    pub(crate) async fn contracts_call(
+        &self,
+        origin: &str,
+        dest: &str,
+        input_data: &str,
+        value: u128,
+    ) -> Result<ContractResult> {
+        let request = json!({
+            "origin": origin,
+            "dest": dest,
+            // Values above `u64::MAX` are not JSON numbers of the node.
+            "value": format!("0x{:x}", value),
+            "gasLimit": self.config().gas_limits.call,
+            "storageDepositLimit": null,
+            "inputData": input_data,
+        });
+        self.rpc().request("contracts_call", json!([request])).await
+    }
+
+    fn require_metadata(&self, contract: &Contract) -> Result<Arc<ContractMetadata>> {
+        self.metadata_of(contract, false)?.0.ok_or_else(|| {
+            Error::InvalidCall(format!("the metadata of {} is not given", contract.name))
+        })
+    }
+}
+
+/// Return the selector followed by the SCALE encoded arguments, in `0x` prefixed hex.
+pub(crate) fn input_data(selector: [u8; 4], arguments: &[Argument]) -> Result<String> {
+    let mut data = selector.to_vec();
+    for argument in arguments {
+        data.extend(argument.encoded()?);
+    }
+    Ok(format!("0x{}", hex::encode(data)))
+}
+
+/// Read the fields which calls and instantiations share.
+pub(crate) fn dry_run(result: &ContractResult, failed: bool) -> Result<DryRun> {
+    let storage_deposit = match (
+        result
+            .storage_deposit
+            .get("charge")
+            .or_else(|| result.storage_deposit.get("Charge")),
+        result
+            .storage_deposit
+            .get("refund")
+            .or_else(|| result.storage_deposit.get("Refund")),
+    ) {
+        (Some(charge), _) => StorageDeposit::Charge(parse_number(charge)?),
+        (None, Some(refund)) => StorageDeposit::Refund(parse_number(refund)?),
+        (None, None) => StorageDeposit::Charge(0),
+    };
+    let debug_message = match &result.debug_message {
+        Some(message) => String::from_utf8_lossy(&decode_hex(message)?).into_owned(),
+        None => String::new(),
+    };
+    let dispatch_error = if failed {
+        let error = result
+            .result
+            .get("Err")
+            .or_else(|| result.result.get("err"))
+            .unwrap_or(&result.result);
+        Some(error.to_string())
+    } else {
+        None
+    };
+
+    Ok(DryRun {
+        gas_consumed: parse_gas(&result.gas_consumed)?,
+        gas_required: parse_gas(&result.gas_required)?,
+        storage_deposit,
+        debug_message,
+        return_value: None,
+        revert: None,
+        dispatch_error,
+        contract_addr: None,
+    })
+}
+
+/// Return the flags and the data of `ExecReturnValue`.
+pub(crate) fn exec_output(output: &Value) -> Result<(u32, Vec<u8>)> {
+    let flags = match &output["flags"] {
+        // Older nodes give the flags as a struct of bits.
+        Value::Object(bits) => bits.get("bits").map(parse_number).transpose()?.unwrap_or(0),
+        flags => parse_number(flags)?,
+    } as u32;
+    let data = decode_hex(output["data"].as_str().unwrap_or("0x"))?;
+    Ok((flags, data))
+}
+
+/// Return why the contract reverted, from the `Err` which the message returned or the flags.
+fn revert_reason(returned: Option<&Value>, flags: u32) -> Option<String> {
+    if let Some(err) = returned.and_then(|x| x.get("Err")) {
+        return Some(match err {
+            Value::String(name) => name.clone(),
+            other => other.to_string(),
+        });
+    }
+    if flags & FLAG_REVERT != 0 {
+        return Some("the contract reverted".to_owned());
+    }
+    None
+}
+
+/// Parse a number which the node gives as a JSON number, or a decimal or hex string.
+fn parse_number(value: &Value) -> Result<u128> {
+    let invalid = || Error::Scale(format!("invalid number {}", value));
+    match value {
+        Value::Number(number) => number.as_u64().map(u128::from).ok_or_else(invalid),
+        Value::String(s) => match s.strip_prefix("0x") {
+            Some(hex) => u128::from_str_radix(hex, 16).map_err(|_| invalid()),
+            None => s.parse().map_err(|_| invalid()),
+        },
+        _ => Err(invalid()),
+    }
+}
+
+/// Parse gas, which nodes with the `Weight` of two dimensions give as
+/// `{refTime, proofSize}`, of which only the computation time is the gas of `ink!` 3.
+fn parse_gas(value: &Value) -> Result<u64> {
+    let ref_time = match value {
+        Value::Object(weight) => weight
+            .get("refTime")
+            .or_else(|| weight.get("ref_time"))
+            .ok_or_else(|| Error::Scale(format!("invalid weight {}", value)))?,
+        value => value,
+    };
+    u64::try_from(parse_number(ref_time)?)
+        .map_err(|_| Error::Scale(format!("gas {} is out of range", value)))
+}
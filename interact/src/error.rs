@@ -0,0 +1,53 @@
+use thiserror::Error;
+
+/// An error from interacting with the chain through the http server or the full node.
+#[derive(Error, Debug)]
+pub enum Error {
+    /// When the request fails to reach the http server or its response is cut.
+    #[error("failed to reach the http server: {0}")]
+    Transport(#[from] reqwest::Error),
+    /// When the http server responds with a status other than 200.
+    #[error("http server responded with {0}")]
+    HttpStatus(reqwest::StatusCode),
+    /// When the http server fails to handle the request, e.g. the full node is unreachable.
+    #[error("http server failed: {0}")]
+    Backend(String),
+    /// When the response is not in the expected form.
+    #[error("failed to decode the response: {0}")]
+    Decode(#[from] serde_json::Error),
+    /// When there is no mnemonic to sign the transaction with.
+    #[error("signer is not configured: {0}")]
+    MissingSigner(String),
+    /// When the contract rejects the message, with the reason given by the contract.
+    #[error("contract reverted: {0}")]
+    ContractReverted(String),
+    /// When the WebSocket connection to the full node fails or times out.
+    #[error("failed to reach the full node: {0}")]
+    WebSocket(String),
+    /// When the full node responds with a JSON-RPC error.
+    #[error("full node responded with error {code}: {message}")]
+    Rpc { code: i64, message: String },
+    /// When SCALE encoded data from the chain cannot be decoded.
+    #[error("failed to decode SCALE data: {0}")]
+    Scale(String),
+    /// When an address is not a valid SS58 address.
+    #[error("invalid address {0}")]
+    InvalidAddress(String),
+    /// When a contract metadata file is not a valid ink! metadata.
+    #[error("invalid metadata: {0}")]
+    Metadata(String),
+    /// When a call does not match the metadata of the contract, e.g. a typo in the message name.
+    #[error("invalid call: {0}")]
+    InvalidCall(String),
+    /// When a contract descriptor is malformed or lacks what the call needs, e.g. the address.
+    #[error("invalid contract {0}")]
+    InvalidContract(String),
+    /// When a transaction does not reach the awaited status before the deadline.
+    #[error("transaction timed out: {0}")]
+    TxTimeout(String),
+    /// When a local file such as the deployment registry cannot be read or written.
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
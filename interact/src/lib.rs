@@ -1,15 +1,34 @@
-use anyhow::Result;
-use dotenv::dotenv;
 use serde::{Deserialize, Serialize};
-use serde_json::{json, Value};
-use std::{env, fmt::Debug};
-
-#[derive(Serialize, Deserialize, Debug)]
-pub enum Contract {
-    SimpleCounter,
-    Treasury,
-    LightClient,
-}
+use std::fmt::Debug;
+
+pub mod argument;
+pub mod block;
+pub mod client;
+pub mod contract;
+pub mod dry_run;
+pub mod error;
+pub mod metadata;
+pub mod psp34;
+pub mod registry;
+pub mod retry;
+pub mod rpc;
+pub mod scale;
+pub mod signer;
+pub mod simple_counter;
+pub mod tx;
+
+pub use argument::{AccountId, Argument, ArgumentType};
+pub use block::{BlockId, BlockSummary};
+pub use client::{ClientConfig, GasLimits, InteractClient};
+pub use contract::Contract;
+pub use dry_run::{DryRun, StorageDeposit};
+pub use error::{Error, Result};
+pub use metadata::ContractMetadata;
+pub use retry::{ErrorClass, RetryPolicy};
+pub use rpc::{ReadBackend, RpcClient};
+pub use signer::{Credential, EnvSigner, KeystoreSigner, MnemonicSigner, Signer};
+pub use simple_counter::SimpleCounterClient;
+pub use tx::{TxEvent, TxHandle, TxInclusion, TxStatus};
 
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all(deserialize = "camelCase"))]
@@ -18,10 +37,21 @@ pub struct Block {
     pub timestamp: u64,
 }
 
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all(deserialize = "camelCase"))]
+pub struct ChainInfo {
+    /// Name of the chain, e.g. Shibuya Testnet.
+    pub chain: String,
+    pub genesis_hash: String,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all(deserialize = "camelCase"))]
 pub struct Account {
+    /// Free and reserved balance of the native token, in planck.
     pub native_token: String,
+    /// Free balance of the native token in planck, which can pay fees.
+    pub free_native_token: String,
     pub meme_token: String,
     pub non_fungible_token: String,
 }
@@ -42,6 +72,18 @@ pub struct ContractTx {
     pub message_name: String,
     pub message_type: String,
     pub tx_hash: String,
+    /// Height of the block which includes the transaction, to track it from with a `TxHandle`.
+    #[serde(default)]
+    pub block_number: u64,
+    /// Events emitted by the contract during the transaction.
+    #[serde(default)]
+    pub events: Vec<ContractEvent>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ContractEvent {
+    pub name: String,
+    pub args: Vec<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -49,190 +91,67 @@ pub struct ContractTx {
 pub struct ContractDeploy {
     pub contract_name: String,
     pub contract_addr: String,
+    pub code_hash: String,
     pub tx_hash: String,
+    pub block_number: u64,
 }
 
-/// Return JSON response from path and data.
-pub async fn get_response(http_server: &str, path: &str, data: Value) -> Result<Value> {
-    let client = reqwest::Client::new();
-    let response = client
-        .post(http_server.to_owned() + path)
-        .json(&data)
-        .send()
-        .await?;
-
-    Ok(match response.status() {
-        reqwest::StatusCode::OK => response.json().await?,
-        other => panic!("fail to get reponse properly: {:?}", other),
-    })
-}
-
-/// Return the current block height.
-pub async fn get_current_height(full_node_uri: &str, http_server: &str) -> Result<Option<u64>> {
-    let path = "current-height";
-    let data = json!({
-        "fullNodeUri": full_node_uri,
-    });
-    let result = get_response(http_server, path, data).await?;
-
-    Ok(result["data"]["height"].as_u64())
-}
-
-/// Return the current block hash and timestamp.
-pub async fn get_block(full_node_uri: &str, http_server: &str, height: u64) -> Result<Block> {
-    let path = "block-info";
-    let data = json!({
-        "fullNodeUri": full_node_uri,
-        "height": height,
-    });
-    let result = get_response(http_server, path, data).await?;
-    let block: Block = serde_json::from_value(result["data"].clone())?;
-
-    Ok(block)
+/// The body of every response of the http server.
+/// The server answers with 200 even if it fails, setting `success` to false.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Response<T> {
+    pub success: bool,
+    pub data: Option<T>,
+    /// Why the server failed.
+    pub msg: Option<String>,
+    /// Why the contract rejected the message, if it did.
+    pub revert: Option<String>,
 }
 
-/// Return the native token, meme token(TBD), nft(TBD) balance of the given account.
-pub async fn query_account(full_node_uri: &str, http_server: &str, addr: &str) -> Result<Account> {
-    let path = "account-info";
-    let data = json!({
-        "fullNodeUri": full_node_uri,
-        "addr": addr,
-    });
-    let result = get_response(http_server, path, data).await?;
-    let account: Account = serde_json::from_value(result["data"].clone())?;
-
-    Ok(account)
+impl<T> Response<T> {
+    /// Return the data, or the error which the server reported.
+    pub fn into_result(self) -> Result<T> {
+        match self {
+            Response {
+                success: true,
+                data: Some(data),
+                ..
+            } => Ok(data),
+            Response { success: true, .. } => {
+                Err(Error::Backend("the response has no data".to_owned()))
+            }
+            Response {
+                revert: Some(reason),
+                ..
+            } => Err(Error::ContractReverted(reason)),
+            Response { msg, .. } => Err(Error::Backend(msg.unwrap_or_default())),
+        }
+    }
 }
 
-/// Transfer the native token to receiver account.
-pub async fn transfer_native_token(
-    full_node_uri: &str,
-    http_server: &str,
-    receiver_public_key: &str,
-    amount: u64,
-    planck_to_one: u8,
-) -> Result<String> {
-    let path = "native-token/transfer";
-    dotenv().expect("failed to read .env file");
-    let data = json!({
-        "fullNodeUri": full_node_uri,
-        "mnemonic": env::var("SIGNER_MNEMONIC").expect("fail to load signer mnemonic").as_str(),
-        "toAddr": receiver_public_key,
-        "amount": amount,
-        "planckToOneNT": planck_to_one,
-    });
-    let result: Value = get_response(http_server, path, data).await?;
-
-    Ok(result["data"]["txHash"].to_string())
+#[derive(Deserialize)]
+pub(crate) struct Height {
+    pub height: u64,
 }
 
-/// Query the state of the deployed contract.
-pub async fn query_contract_state(
-    full_node_uri: &str,
-    http_server: &str,
-    contract_addr: &str,
-    contract_name: Contract,
-    field: &str,
-) -> Result<ContractQuery> {
-    let path = "contract-state";
-    let contract = match contract_name {
-        Contract::SimpleCounter => "simple_counter",
-        Contract::Treasury => "treasury",
-        Contract::LightClient => "light_client",
-    };
-    let data = json!({
-        "fullNodeUri": full_node_uri,
-        "contractAddr": contract_addr,
-        "contractName": contract,
-        "field": field,
-    });
-    let result = get_response(http_server, path, data).await?;
-    let contract_tx: ContractQuery = serde_json::from_value(result["data"].clone())?;
-
-    Ok(contract_tx)
+#[derive(Deserialize)]
+pub(crate) struct Address {
+    pub address: String,
 }
 
-/// Execute the method(Send a transaction) of the deployed contract.
-pub async fn execute_contract_method(
-    full_node_uri: &str,
-    http_server: &str,
-    contract_addr: &str,
-    contract_name: Contract,
-    method_name: &str,
-    arguments: Vec<&str>,
-) -> Result<ContractTx> {
-    let path = "contract-method/execute";
-    dotenv().expect("failed to read .env file");
-    let contract = match contract_name {
-        Contract::SimpleCounter => "simple_counter",
-        Contract::Treasury => "treasury",
-        Contract::LightClient => "light_client",
-    };
-    let data = json!({
-        "fullNodeUri": full_node_uri,
-        "mnemonic": env::var("SIGNER_MNEMONIC").expect("fail to load signer mnemonic").as_str(),
-        "contractAddr": contract_addr,
-        "contractName": contract,
-        "methodName": method_name,
-        "arguments": arguments,
-    });
-    let result: Value = get_response(http_server, path, data).await?;
-    let contract_tx: ContractTx = serde_json::from_value(result["data"].clone())?;
-
-    Ok(contract_tx)
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct TxHash {
+    pub tx_hash: String,
 }
 
-/// Deploy the contract with its name.
-/// e.g. simple_counter, light_client, treasury.
-pub async fn deploy_contract(
-    full_node_uri: &str,
-    http_server: &str,
-    contract_name: Contract,
-    arguments: Vec<&str>,
-) -> Result<ContractDeploy> {
-    let path = "contract/deploy";
-    dotenv().expect("failed to read .env file");
-    let contract = match contract_name {
-        Contract::SimpleCounter => "simple_counter",
-        Contract::Treasury => "treasury",
-        Contract::LightClient => "light_client",
-    };
-    let data = json!({
-        "fullNodeUri": full_node_uri,
-        "mnemonic": env::var("SIGNER_MNEMONIC").expect("fail to load signer mnemonic").as_str(),
-        "contractName": contract,
-        "arguments": arguments,
-    });
-    let result = get_response(http_server, path, data).await?;
-    let contract_deploy: ContractDeploy = serde_json::from_value(result["data"].clone())?;
-
-    Ok(contract_deploy)
+#[derive(Deserialize)]
+pub(crate) struct Exists {
+    pub exists: bool,
 }
 
-/// Deploy the contract with code hash.
-pub async fn deploy_contract_with_code_hash(
-    full_node_uri: &str,
-    http_server: &str,
-    contract_name: Contract,
-    arguments: Vec<&str>,
-    salt: &str,
-) -> Result<ContractDeploy> {
-    let path = "contract-from-code-hash/deploy";
-    dotenv().expect("failed to read .env file");
-    let contract = match contract_name {
-        Contract::SimpleCounter => "simple_counter",
-        Contract::Treasury => "treasury",
-        Contract::LightClient => "light_client",
-    };
-    let data = json!({
-        "fullNodeUri": full_node_uri,
-        "mnemonic": std::env::var("SIGNER_MNEMONIC").expect("fail to load signer mnemonic").as_str(),
-        "contractName": contract,
-        "arguments": arguments,
-        "salt": salt,
-    });
-    let result = get_response(http_server, path, data).await?;
-    let contract_deploy: ContractDeploy = serde_json::from_value(result["data"].clone())?;
-
-    Ok(contract_deploy)
+/// Decode bytes in hex, with or without the `0x` prefix, as the chain and the metadata give them.
+pub(crate) fn decode_hex(value: &str) -> Result<Vec<u8>> {
+    hex::decode(value.trim_start_matches("0x"))
+        .map_err(|e| Error::Scale(format!("invalid hex {}: {}", value, e)))
 }
@@ -0,0 +1,461 @@
+//! ink! metadata bundles (`.contract` files) of the version 3.
+
+use crate::*;
+use codec::{Compact, Decode};
+use serde_json::{json, Value};
+use std::path::Path;
+
+/// The metadata of a contract, with its Wasm blob if bundled.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ContractMetadata {
+    pub source: Source,
+    pub contract: ContractSummary,
+    pub spec: ContractSpec,
+    /// Every type which the spec refers to, by its ID.
+    pub types: Vec<PortableType>,
+}
+
+/// The layout of a `.contract` file.
+#[derive(Deserialize)]
+struct Bundle {
+    source: Source,
+    contract: ContractSummary,
+    #[serde(rename = "V3")]
+    v3: BundleV3,
+}
+
+#[derive(Deserialize)]
+struct BundleV3 {
+    spec: ContractSpec,
+    types: Vec<PortableType>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Source {
+    /// Code hash of the Wasm blob.
+    pub hash: String,
+    pub language: String,
+    pub compiler: String,
+    /// Wasm blob in `0x` prefixed hex, which `.json` metadata files do not have.
+    pub wasm: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ContractSummary {
+    pub name: String,
+    pub version: String,
+    #[serde(default)]
+    pub authors: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ContractSpec {
+    pub constructors: Vec<ConstructorSpec>,
+    pub messages: Vec<MessageSpec>,
+    #[serde(default)]
+    pub events: Vec<EventSpec>,
+    #[serde(default)]
+    pub docs: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConstructorSpec {
+    pub label: String,
+    /// Selector in `0x` prefixed hex.
+    pub selector: String,
+    pub args: Vec<ArgSpec>,
+    #[serde(default)]
+    pub payable: bool,
+    #[serde(default)]
+    pub docs: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageSpec {
+    pub label: String,
+    /// Selector in `0x` prefixed hex.
+    pub selector: String,
+    pub args: Vec<ArgSpec>,
+    /// `None` if the message returns nothing.
+    pub return_type: Option<TypeSpec>,
+    /// Whether the message changes the state, i.e. must be sent as a transaction.
+    pub mutates: bool,
+    #[serde(default)]
+    pub payable: bool,
+    #[serde(default)]
+    pub docs: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ArgSpec {
+    pub label: String,
+    #[serde(rename = "type")]
+    pub type_spec: TypeSpec,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EventSpec {
+    pub label: String,
+    pub args: Vec<EventArgSpec>,
+    #[serde(default)]
+    pub docs: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EventArgSpec {
+    pub label: String,
+    pub indexed: bool,
+    #[serde(rename = "type")]
+    pub type_spec: TypeSpec,
+}
+
+/// A reference to a type of `ContractMetadata::types`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TypeSpec {
+    /// e.g. `["AccountId"]`, `["Vec"]`.
+    pub display_name: Vec<String>,
+    #[serde(rename = "type")]
+    pub id: u32,
+}
+
+impl TypeSpec {
+    /// Return the last segment of the display name, which `Argument::type_name` refers to.
+    pub fn name(&self) -> &str {
+        self.display_name
+            .last()
+            .map(|x| x.as_str())
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PortableType {
+    pub id: u32,
+    #[serde(rename = "type")]
+    pub ty: Type,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Type {
+    pub def: TypeDef,
+    /// e.g. `["ink_env", "types", "AccountId"]`, empty for the built-in types.
+    #[serde(default)]
+    pub path: Vec<String>,
+    #[serde(default)]
+    pub params: Vec<TypeParam>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TypeParam {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub ty: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub enum TypeDef {
+    /// e.g. `u64`, `bool`, `str`.
+    Primitive(String),
+    Composite {
+        #[serde(default)]
+        fields: Vec<Field>,
+    },
+    Variant {
+        #[serde(default)]
+        variants: Vec<Variant>,
+    },
+    Sequence {
+        #[serde(rename = "type")]
+        ty: u32,
+    },
+    Array {
+        len: u32,
+        #[serde(rename = "type")]
+        ty: u32,
+    },
+    Tuple(Vec<u32>),
+    Compact {
+        #[serde(rename = "type")]
+        ty: u32,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Field {
+    pub name: Option<String>,
+    #[serde(rename = "type")]
+    pub ty: u32,
+    pub type_name: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Variant {
+    pub name: String,
+    pub index: u8,
+    #[serde(default)]
+    pub fields: Vec<Field>,
+}
+
+impl std::str::FromStr for ContractMetadata {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let bundle: Bundle = serde_json::from_str(s).map_err(|e| Error::Metadata(e.to_string()))?;
+        Ok(Self {
+            source: bundle.source,
+            contract: bundle.contract,
+            spec: bundle.v3.spec,
+            types: bundle.v3.types,
+        })
+    }
+}
+
+impl ContractMetadata {
+    /// Read a `.contract` bundle or a `.json` metadata file.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        std::fs::read_to_string(path)?.parse()
+    }
+
+    /// Return the Wasm blob of the contract.
+    pub fn wasm(&self) -> Result<Vec<u8>> {
+        let wasm = self
+            .source
+            .wasm
+            .as_ref()
+            .ok_or_else(|| Error::Metadata("the Wasm blob is not bundled".to_owned()))?;
+        decode_hex(wasm)
+    }
+
+    pub fn constructor(&self, label: &str) -> Result<&ConstructorSpec> {
+        self.spec
+            .constructors
+            .iter()
+            .find(|x| x.label == label)
+            .ok_or_else(|| {
+                Error::InvalidCall(format!(
+                    "{} has no constructor {}",
+                    self.contract.name, label
+                ))
+            })
+    }
+
+    pub fn message(&self, label: &str) -> Result<&MessageSpec> {
+        self.spec
+            .messages
+            .iter()
+            .find(|x| x.label == label)
+            .ok_or_else(|| {
+                Error::InvalidCall(format!("{} has no message {}", self.contract.name, label))
+            })
+    }
+
+    pub fn event(&self, label: &str) -> Option<&EventSpec> {
+        self.spec.events.iter().find(|x| x.label == label)
+    }
+
+    pub fn type_of(&self, id: u32) -> Option<&Type> {
+        self.types.iter().find(|x| x.id == id).map(|x| &x.ty)
+    }
+
+    /// Check the arguments of a call to the constructor.
+    pub fn validate_constructor(
+        &self,
+        label: &str,
+        arguments: &[Argument],
+    ) -> Result<&ConstructorSpec> {
+        let constructor = self.constructor(label)?;
+        self.validate_arguments(label, &constructor.args, arguments)?;
+        Ok(constructor)
+    }
+
+    /// Check the arguments of a call to the message.
+    pub fn validate_message(&self, label: &str, arguments: &[Argument]) -> Result<&MessageSpec> {
+        let message = self.message(label)?;
+        self.validate_arguments(label, &message.args, arguments)?;
+        Ok(message)
+    }
+
+    fn validate_arguments(
+        &self,
+        label: &str,
+        specs: &[ArgSpec],
+        arguments: &[Argument],
+    ) -> Result<()> {
+        if specs.len() != arguments.len() {
+            return Err(Error::InvalidCall(format!(
+                "{} takes {} arguments, got {}",
+                label,
+                specs.len(),
+                arguments.len()
+            )));
+        }
+        for (spec, argument) in specs.iter().zip(arguments) {
+            if spec.type_spec.name() != argument.type_name {
+                return Err(Error::InvalidCall(format!(
+                    "{} of {} is {}, got {}",
+                    spec.label,
+                    label,
+                    spec.type_spec.name(),
+                    argument.type_name
+                )));
+            }
+            let encoded_len = argument.encoded()?.len();
+            match self.encoded_size(spec.type_spec.id) {
+                Some(size) if size != encoded_len => {
+                    return Err(Error::InvalidCall(format!(
+                        "{} of {} takes {} bytes, got {}",
+                        spec.label, label, size, encoded_len
+                    )))
+                }
+                _ => (),
+            }
+        }
+        Ok(())
+    }
+
+    /// Return the size of the SCALE encoded values of the type, if they have a fixed size.
+    pub fn encoded_size(&self, id: u32) -> Option<usize> {
+        let sum = |ids: &mut dyn Iterator<Item = u32>| {
+            ids.map(|x| self.encoded_size(x)).sum::<Option<usize>>()
+        };
+        match &self.type_of(id)?.def {
+            TypeDef::Primitive(primitive) => match primitive.as_str() {
+                "bool" | "u8" | "i8" => Some(1),
+                "u16" | "i16" => Some(2),
+                "u32" | "i32" | "char" => Some(4),
+                "u64" | "i64" => Some(8),
+                "u128" | "i128" => Some(16),
+                "u256" | "i256" => Some(32),
+                _ => None,
+            },
+            TypeDef::Array { len, ty } => Some(*len as usize * self.encoded_size(*ty)?),
+            TypeDef::Composite { fields } => sum(&mut fields.iter().map(|x| x.ty)),
+            TypeDef::Tuple(ids) => sum(&mut ids.iter().copied()),
+            TypeDef::Variant { .. } | TypeDef::Sequence { .. } | TypeDef::Compact { .. } => None,
+        }
+    }
+}
+
+impl ContractMetadata {
+    /// Decode a SCALE encoded value of the type into JSON, consuming it from the input.
+    ///
+    /// Structs become objects, enums the name of their variant or an object keyed by it,
+    /// byte arrays `0x` prefixed hex and 128 bits numbers strings.
+    pub fn decode_value(&self, id: u32, input: &mut &[u8]) -> Result<Value> {
+        let ty = self
+            .type_of(id)
+            .ok_or_else(|| Error::Metadata(format!("no type of id {}", id)))?;
+        let scale = |e: codec::Error| Error::Scale(format!("invalid value of type {}: {}", id, e));
+        let value = match &ty.def {
+            TypeDef::Primitive(primitive) => match primitive.as_str() {
+                "bool" => json!(bool::decode(input).map_err(scale)?),
+                "char" => json!(u32::decode(input).map_err(scale)?),
+                "str" => json!(String::decode(input).map_err(scale)?),
+                "u8" => json!(u8::decode(input).map_err(scale)?),
+                "u16" => json!(u16::decode(input).map_err(scale)?),
+                "u32" => json!(u32::decode(input).map_err(scale)?),
+                "u64" => json!(u64::decode(input).map_err(scale)?),
+                "u128" => json!(u128::decode(input).map_err(scale)?.to_string()),
+                "i8" => json!(i8::decode(input).map_err(scale)?),
+                "i16" => json!(i16::decode(input).map_err(scale)?),
+                "i32" => json!(i32::decode(input).map_err(scale)?),
+                "i64" => json!(i64::decode(input).map_err(scale)?),
+                "i128" => json!(i128::decode(input).map_err(scale)?.to_string()),
+                other => return Err(Error::Metadata(format!("unsupported primitive {}", other))),
+            },
+            TypeDef::Composite { fields } => self.decode_fields(fields, input)?,
+            TypeDef::Variant { variants } => {
+                let index = u8::decode(input).map_err(scale)?;
+                let variant = variants
+                    .iter()
+                    .find(|x| x.index == index)
+                    .ok_or_else(|| Error::Scale(format!("no variant {} of type {}", index, id)))?;
+                if variant.fields.is_empty() {
+                    json!(variant.name)
+                } else {
+                    json!({ &variant.name: self.decode_fields(&variant.fields, input)? })
+                }
+            }
+            TypeDef::Sequence { ty } => {
+                let len = Compact::<u32>::decode(input).map_err(scale)?.0;
+                self.decode_items(*ty, len, input)?
+            }
+            TypeDef::Array { len, ty } => self.decode_items(*ty, *len, input)?,
+            TypeDef::Tuple(ids) => Value::Array(
+                ids.iter()
+                    .map(|x| self.decode_value(*x, input))
+                    .collect::<Result<_>>()?,
+            ),
+            TypeDef::Compact { .. } => {
+                json!(Compact::<u128>::decode(input).map_err(scale)?.0.to_string())
+            }
+        };
+        Ok(value)
+    }
+
+    fn decode_fields(&self, fields: &[Field], input: &mut &[u8]) -> Result<Value> {
+        // A newtype is its only field, and a tuple struct the list of its fields.
+        if fields.len() == 1 && fields[0].name.is_none() {
+            return self.decode_value(fields[0].ty, input);
+        }
+        if fields.iter().any(|x| x.name.is_none()) {
+            return Ok(Value::Array(
+                fields
+                    .iter()
+                    .map(|x| self.decode_value(x.ty, input))
+                    .collect::<Result<_>>()?,
+            ));
+        }
+        let mut object = serde_json::Map::new();
+        for field in fields {
+            let name = field.name.clone().unwrap_or_default();
+            object.insert(name, self.decode_value(field.ty, input)?);
+        }
+        Ok(Value::Object(object))
+    }
+
+    fn decode_items(&self, ty: u32, len: u32, input: &mut &[u8]) -> Result<Value> {
+        let is_byte = matches!(
+            self.type_of(ty).map(|x| &x.def),
+            Some(TypeDef::Primitive(primitive)) if primitive == "u8"
+        );
+        if is_byte {
+            let len = len as usize;
+            if input.len() < len {
+                return Err(Error::Scale(format!("expected {} bytes", len)));
+            }
+            let (bytes, rest) = input.split_at(len);
+            *input = rest;
+            return Ok(json!(format!("0x{}", hex::encode(bytes))));
+        }
+        Ok(Value::Array(
+            (0..len)
+                .map(|_| self.decode_value(ty, input))
+                .collect::<Result<_>>()?,
+        ))
+    }
+}
+
+/// Parse a selector in `0x` prefixed hex.
+pub fn parse_selector(selector: &str) -> Result<[u8; 4]> {
+    let bytes = decode_hex(selector)?;
+    bytes
+        .try_into()
+        .map_err(|_| Error::Metadata(format!("invalid selector {}", selector)))
+}
+
+impl ConstructorSpec {
+    pub fn selector(&self) -> Result<[u8; 4]> {
+        parse_selector(&self.selector)
+    }
+}
+
+impl MessageSpec {
+    pub fn selector(&self) -> Result<[u8; 4]> {
+        parse_selector(&self.selector)
+    }
+}
@@ -0,0 +1,103 @@
+//! Reading the collections of non-fungible tokens which implement PSP34 of `openbrush`.
+
+use crate::dry_run::{dry_run, exec_output, input_data, FLAG_REVERT};
+use crate::*;
+use codec::{Decode, Encode};
+use serde_json::Value;
+use std::str::FromStr;
+
+/// Selector of `PSP34::owner_of`.
+const SELECTOR_OWNER_OF: [u8; 4] = [0x11, 0x68, 0x62, 0x4d];
+
+/// ID of a token in a PSP34 collection.
+#[derive(Encode, Decode, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Id {
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    U128(u128),
+    Bytes(Vec<u8>),
+}
+
+impl FromStr for Id {
+    type Err = Error;
+
+    /// Parse the JSON of polkadot.js, e.g. `{"u64":7}` or `{"bytes":"0x0102"}`.
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || Error::Scale(format!("invalid PSP34 ID {}", s));
+        let id: serde_json::Map<String, Value> = serde_json::from_str(s).map_err(|_| invalid())?;
+        let (variant, value) = match id.iter().next() {
+            Some(entry) if id.len() == 1 => entry,
+            _ => return Err(invalid()),
+        };
+        if variant.eq_ignore_ascii_case("bytes") {
+            let bytes = value.as_str().ok_or_else(invalid)?;
+            return decode_hex(bytes).map(Id::Bytes);
+        }
+        // Numbers above 2^53 are given in `0x` prefixed hex.
+        let number = match value {
+            Value::Number(number) => number.as_u64().map(u128::from),
+            Value::String(s) => match s.strip_prefix("0x") {
+                Some(hex) => u128::from_str_radix(hex, 16).ok(),
+                None => s.parse().ok(),
+            },
+            _ => None,
+        }
+        .ok_or_else(invalid)?;
+        let id = match variant.to_ascii_lowercase().as_str() {
+            "u8" => u8::try_from(number).ok().map(Id::U8),
+            "u16" => u16::try_from(number).ok().map(Id::U16),
+            "u32" => u32::try_from(number).ok().map(Id::U32),
+            "u64" => u64::try_from(number).ok().map(Id::U64),
+            "u128" => Some(Id::U128(number)),
+            _ => None,
+        };
+        id.ok_or_else(invalid)
+    }
+}
+
+impl InteractClient {
+    /// Return the owner of the token in the PSP34 collection at the address,
+    /// or `None` if the token does not exist.
+    pub async fn psp34_owner_of(&self, collection: &str, id: &Id) -> Result<Option<AccountId>> {
+        let input_data = input_data(SELECTOR_OWNER_OF, &[Argument::custom("Id", id)])?;
+        // Anyone may read the owner, so the collection itself is the origin.
+        let result = self
+            .contracts_call(collection, collection, &input_data, 0)
+            .await?;
+        let output = result.result.get("Ok").or_else(|| result.result.get("ok"));
+        dry_run(&result, output.is_none())?.check()?;
+        let (flags, data) = exec_output(output.unwrap_or(&Value::Null))?;
+        if flags & FLAG_REVERT != 0 {
+            return Err(Error::ContractReverted(format!(
+                "{} reverted owner_of {:?}",
+                collection, id
+            )));
+        }
+        Option::<AccountId>::decode(&mut data.as_slice())
+            .map_err(|e| Error::Scale(format!("invalid owner of {:?}: {}", id, e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_ids() {
+        assert_eq!(r#"{"u64":7}"#.parse::<Id>().unwrap(), Id::U64(7));
+        assert_eq!(r#"{"U8":"7"}"#.parse::<Id>().unwrap(), Id::U8(7));
+        assert_eq!(
+            r#"{"u128":"0xde0b6b3a7640000"}"#.parse::<Id>().unwrap(),
+            Id::U128(1_000_000_000_000_000_000)
+        );
+        assert_eq!(
+            r#"{"bytes":"0x0102"}"#.parse::<Id>().unwrap(),
+            Id::Bytes(vec![1, 2])
+        );
+        assert!(r#"{"u8":256}"#.parse::<Id>().is_err());
+        assert!(r#"{"u64":7,"u8":7}"#.parse::<Id>().is_err());
+        assert!("7".parse::<Id>().is_err());
+    }
+}
@@ -0,0 +1,101 @@
+use crate::{Contract, ContractDeploy, Error, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// A contract deployed by the relayer.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(try_from = "RawDeployment")]
+pub struct Deployment {
+    /// The contract at the deployed address, which it always has.
+    contract: Contract,
+    pub code_hash: String,
+    pub tx_hash: String,
+    pub block_number: u64,
+}
+
+/// A deployment as written in the registry file, before its contract is checked for an address.
+#[derive(Deserialize)]
+struct RawDeployment {
+    contract: Contract,
+    code_hash: String,
+    tx_hash: String,
+    block_number: u64,
+}
+
+impl TryFrom<RawDeployment> for Deployment {
+    type Error = Error;
+
+    fn try_from(raw: RawDeployment) -> Result<Self> {
+        raw.contract.address()?;
+        Ok(Self {
+            contract: raw.contract,
+            code_hash: raw.code_hash,
+            tx_hash: raw.tx_hash,
+            block_number: raw.block_number,
+        })
+    }
+}
+
+impl Deployment {
+    pub fn new(contract: Contract, deploy: &ContractDeploy) -> Self {
+        Self {
+            contract: contract.at(&deploy.contract_addr),
+            code_hash: deploy.code_hash.clone(),
+            tx_hash: deploy.tx_hash.clone(),
+            block_number: deploy.block_number,
+        }
+    }
+
+    /// Return the contract at the deployed address.
+    pub fn contract(&self) -> &Contract {
+        &self.contract
+    }
+
+    pub fn contract_addr(&self) -> &str {
+        self.contract
+            .address
+            .as_deref()
+            .expect("deployments are created with their addresses")
+    }
+}
+
+/// A JSON file which keeps the list of the deployed contracts.
+#[derive(Debug)]
+pub struct DeploymentRegistry {
+    path: PathBuf,
+    deployments: Vec<Deployment>,
+}
+
+impl DeploymentRegistry {
+    /// Open the registry file, or start an empty registry if the file does not exist yet.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_owned();
+        let deployments = if path.exists() {
+            serde_json::from_str(&std::fs::read_to_string(&path)?)?
+        } else {
+            Vec::new()
+        };
+
+        Ok(Self { path, deployments })
+    }
+
+    pub fn deployments(&self) -> &[Deployment] {
+        &self.deployments
+    }
+
+    /// Return the latest deployment of the contract of the name.
+    pub fn find(&self, name: &str) -> Option<&Deployment> {
+        self.deployments
+            .iter()
+            .rev()
+            .find(|x| x.contract().name == name)
+    }
+
+    /// Append a deployment and write the registry back to its file.
+    pub fn append(&mut self, deployment: Deployment) -> Result<()> {
+        self.deployments.push(deployment);
+        std::fs::write(&self.path, serde_json::to_string_pretty(&self.deployments)?)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,129 @@
+//! Retrying the failed requests to the http server and the full node.
+
+use crate::*;
+use rand::Rng;
+use std::future::Future;
+use std::time::Duration;
+
+/// A kind of failure which a request may be retried after.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorClass {
+    /// The connection could not be made, so the request was not received.
+    Connect,
+    /// The request was sent but no response came in time.
+    Timeout,
+    /// The http server responded with a 5xx status.
+    ServerError,
+    /// The http server failed to handle the request, e.g. the full node behind it is unreachable.
+    Backend,
+    /// The WebSocket connection to the full node failed or timed out.
+    WebSocket,
+}
+
+impl Error {
+    /// Return the kind of failure, if a retry may succeed.
+    pub fn class(&self) -> Option<ErrorClass> {
+        match self {
+            Error::Transport(e) if e.is_connect() => Some(ErrorClass::Connect),
+            Error::Transport(e) if e.is_timeout() => Some(ErrorClass::Timeout),
+            Error::HttpStatus(status) if status.is_server_error() => Some(ErrorClass::ServerError),
+            Error::Backend(_) => Some(ErrorClass::Backend),
+            Error::WebSocket(_) => Some(ErrorClass::WebSocket),
+            _ => None,
+        }
+    }
+}
+
+/// How an `InteractClient` retries the failed requests.
+///
+/// Transactions are retried only when the connection could not be made,
+/// since any other failure may happen after the transaction is submitted.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct RetryPolicy {
+    /// Attempts of each request including the first one, which is never retried if 1.
+    pub max_attempts: u32,
+    /// Backoff before the first retry in milliseconds, doubled after each retry.
+    pub initial_backoff_ms: u64,
+    /// Limit of the backoff in milliseconds.
+    pub max_backoff_ms: u64,
+    /// Fraction of the backoff which is randomly cut, between 0 and 1,
+    /// so that clients failing together do not retry together.
+    pub jitter: f64,
+    /// Failures after which the reads are retried.
+    pub retry_on: Vec<ErrorClass>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff_ms: 500,
+            max_backoff_ms: 10_000,
+            jitter: 0.5,
+            retry_on: vec![
+                ErrorClass::Connect,
+                ErrorClass::Timeout,
+                ErrorClass::ServerError,
+                ErrorClass::WebSocket,
+            ],
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy which never retries.
+    pub fn never() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    /// Return whether a request is retried after the error.
+    /// Requests which are not idempotent, i.e. transactions, are retried only if never received.
+    pub fn should_retry(&self, error: &Error, idempotent: bool) -> bool {
+        match error.class() {
+            Some(ErrorClass::Connect) if !idempotent => {
+                self.retry_on.contains(&ErrorClass::Connect)
+            }
+            Some(class) if idempotent => self.retry_on.contains(&class),
+            _ => false,
+        }
+    }
+
+    /// Return the backoff before the retry of the given number, starting from 1, without jitter.
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let backoff = self.initial_backoff_ms.saturating_mul(
+            1u64.checked_shl(retry.saturating_sub(1))
+                .unwrap_or(u64::MAX),
+        );
+        Duration::from_millis(backoff.min(self.max_backoff_ms))
+    }
+
+    fn jittered_backoff(&self, retry: u32) -> Duration {
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        self.backoff(retry)
+            .mul_f64(1.0 - jitter * rand::thread_rng().gen::<f64>())
+    }
+
+    /// Run the request until it succeeds, fails in a way which is not retried,
+    /// or runs out of attempts.
+    pub async fn run<T, F, Fut>(&self, idempotent: bool, mut request: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut attempt = 1;
+        loop {
+            match request().await {
+                Err(e) if attempt < self.max_attempts && self.should_retry(&e, idempotent) => {
+                    tokio::time::sleep(self.jittered_backoff(attempt)).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
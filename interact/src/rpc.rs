@@ -0,0 +1,557 @@
+//! Reading the chain through the Substrate JSON-RPC API of the full node,
+//! without the http server.
+
+use crate::*;
+use blake2::digest::consts::U16;
+use blake2::{Blake2b, Blake2b512, Digest};
+use codec::Decode;
+use futures::channel::oneshot;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hasher;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+use twox_hash::XxHash64;
+
+/// Which backend an `InteractClient` reads the chain with.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReadBackend {
+    /// Through the http server, which also sends the transactions.
+    Http,
+    /// Directly from the full node over JSON-RPC.
+    JsonRpc,
+}
+
+impl Default for ReadBackend {
+    fn default() -> Self {
+        ReadBackend::Http
+    }
+}
+
+/// A client of the JSON-RPC API of a full node, sharing one WebSocket connection
+/// among its requests and opening another when it closes.
+#[derive(Debug)]
+pub struct RpcClient {
+    uri: String,
+    timeout: Duration,
+    retry: RetryPolicy,
+    next_id: AtomicU64,
+    connection: futures::lock::Mutex<Option<Arc<Connection>>>,
+}
+
+#[derive(Deserialize)]
+struct RpcResponse {
+    result: Option<Value>,
+    error: Option<RpcError>,
+}
+
+#[derive(Deserialize)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Header {
+    parent_hash: String,
+    /// Block number in `0x` prefixed hex.
+    number: String,
+    state_root: String,
+    extrinsics_root: String,
+    digest: HeaderDigest,
+}
+
+#[derive(Deserialize)]
+struct HeaderDigest {
+    /// SCALE encoded `DigestItem`s in `0x` prefixed hex.
+    logs: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct SignedBlock {
+    block: BlockBody,
+}
+
+#[derive(Deserialize)]
+struct BlockBody {
+    header: Header,
+    extrinsics: Vec<String>,
+}
+
+/// Index of `DigestItem::PreRuntime`, which tells the author of the block.
+const DIGEST_PRE_RUNTIME: u8 = 6;
+
+/// Which session validator authored a block, as the `PreRuntime` digest of its engine tells.
+enum AuthorSlot {
+    /// Slot of Aura, authored by the validator at the slot modulo the number of validators.
+    Aura(u64),
+    /// Index of the authority of BABE.
+    Babe(u32),
+}
+
+/// `AccountInfo` of `frame_system`, with `AccountData` of `pallet_balances`.
+#[derive(Decode)]
+struct AccountInfo {
+    _nonce: u32,
+    _consumers: u32,
+    _providers: u32,
+    _sufficients: u32,
+    free: u128,
+    reserved: u128,
+}
+
+impl RpcClient {
+    pub fn new(uri: &str, timeout: Duration) -> Self {
+        Self {
+            uri: uri.to_owned(),
+            timeout,
+            retry: RetryPolicy::default(),
+            next_id: AtomicU64::new(1),
+            connection: futures::lock::Mutex::new(None),
+        }
+    }
+
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Call the method and return its result, retrying it under the retry policy.
+    /// The method must not change the chain.
+    pub async fn request<T: DeserializeOwned>(&self, method: &str, params: Value) -> Result<T> {
+        self.retry
+            .run(true, || self.request_once(method, &params))
+            .await
+    }
+
+    async fn request_once<T: DeserializeOwned>(&self, method: &str, params: &Value) -> Result<T> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": method,
+            "params": params,
+        })
+        .to_string();
+        let response = self
+            .connection()
+            .await?
+            .send(id, request, self.timeout)
+            .await
+            .map_err(|e| match e {
+                Error::WebSocket(reason) => {
+                    Error::WebSocket(format!("{} to {}: {}", method, self.uri, reason))
+                }
+                e => e,
+            })?;
+
+        let response: RpcResponse = serde_json::from_str(&response)?;
+        if let Some(RpcError { code, message }) = response.error {
+            return Err(Error::Rpc { code, message });
+        }
+        Ok(serde_json::from_value(
+            response.result.unwrap_or(Value::Null),
+        )?)
+    }
+
+    /// Return the open connection, opening one if there is none or the last one closed.
+    async fn connection(&self) -> Result<Arc<Connection>> {
+        let mut connection = self.connection.lock().await;
+        match &*connection {
+            Some(open) if !open.is_closed() => Ok(Arc::clone(open)),
+            _ => {
+                let open = Arc::new(Connection::open(&self.uri, self.timeout).await?);
+                *connection = Some(Arc::clone(&open));
+                Ok(open)
+            }
+        }
+    }
+
+    /// Return the height of the best block.
+    pub async fn get_current_height(&self) -> Result<u64> {
+        let header: Header = self.request("chain_getHeader", json!([])).await?;
+        parse_hex_number(&header.number)
+    }
+
+    /// Return the hash of the block at the height.
+    pub async fn get_block_hash(&self, height: u64) -> Result<String> {
+        let hash: Option<String> = self.request("chain_getBlockHash", json!([height])).await?;
+        hash.ok_or_else(|| Error::Rpc {
+            code: 0,
+            message: format!("no block at height {}", height),
+        })
+    }
+
+    /// Return the hash and the timestamp of the block at the height.
+    pub async fn get_block(&self, height: u64) -> Result<Block> {
+        let block_hash = self.get_block_hash(height).await?;
+        let key = storage_key("Timestamp", "Now", &[]);
+        let storage = self
+            .get_storage(&key, Some(&block_hash))
+            .await?
+            .ok_or_else(|| Error::Rpc {
+                code: 0,
+                message: format!("no timestamp in block {}", block_hash),
+            })?;
+        let timestamp = u64::decode(&mut storage.as_slice())
+            .map_err(|e| Error::Scale(format!("invalid timestamp: {}", e)))?;
+
+        Ok(Block {
+            block_hash,
+            timestamp,
+        })
+    }
+
+    /// Return the header, the number of extrinsics and the author of the block.
+    pub async fn get_block_summary(&self, block: &BlockId) -> Result<BlockSummary> {
+        let block_hash = match block {
+            BlockId::Height(height) => self.get_block_hash(*height).await?,
+            BlockId::Hash(hash) => hash.clone(),
+        };
+        let block: Option<SignedBlock> =
+            self.request("chain_getBlock", json!([block_hash])).await?;
+        let BlockBody { header, extrinsics } = block
+            .ok_or_else(|| Error::Rpc {
+                code: 0,
+                message: format!("no block {}", block_hash),
+            })?
+            .block;
+        let author = self.get_author(&header).await?;
+
+        Ok(BlockSummary {
+            block_hash,
+            block_number: parse_hex_number(&header.number)?,
+            parent_hash: header.parent_hash,
+            state_root: header.state_root,
+            extrinsics_root: header.extrinsics_root,
+            extrinsic_count: extrinsics.len() as u32,
+            author,
+        })
+    }
+
+    /// Return the account ID of the author of the block, in `0x` prefixed hex.
+    async fn get_author(&self, header: &Header) -> Result<Option<String>> {
+        let slot = match author_slot(&header.digest.logs)? {
+            Some(slot) => slot,
+            None => return Ok(None),
+        };
+        // The author is chosen among the validators before the block, which may change them.
+        let key = storage_key("Session", "Validators", &[]);
+        let validators = match self.get_storage(&key, Some(&header.parent_hash)).await? {
+            Some(storage) => Vec::<[u8; 32]>::decode(&mut storage.as_slice())
+                .map_err(|e| Error::Scale(format!("invalid validators: {}", e)))?,
+            None => return Ok(None),
+        };
+        let index = match slot {
+            AuthorSlot::Aura(_) if validators.is_empty() => return Ok(None),
+            AuthorSlot::Aura(slot) => (slot % validators.len() as u64) as usize,
+            AuthorSlot::Babe(index) => index as usize,
+        };
+        Ok(validators
+            .get(index)
+            .map(|x| format!("0x{}", hex::encode(x))))
+    }
+
+    /// Return the native token balance of the account, the sum of its free and reserved balance,
+    /// and its free balance.
+    pub async fn query_account(&self, addr: &str) -> Result<Account> {
+        let account_id = decode_ss58(addr)?;
+        let key = storage_key("System", "Account", &blake2_128_concat(&account_id));
+        let (free, reserved) = match self.get_storage(&key, None).await? {
+            Some(storage) => {
+                let info = AccountInfo::decode(&mut storage.as_slice())
+                    .map_err(|e| Error::Scale(format!("invalid account info: {}", e)))?;
+                (info.free, info.reserved)
+            }
+            // The account does not exist until it receives the existential deposit.
+            None => (0, 0),
+        };
+
+        let total = free
+            .checked_add(reserved)
+            .ok_or_else(|| Error::Scale(format!("balance of {} overflows", addr)))?;
+
+        Ok(Account {
+            native_token: total.to_string(),
+            free_native_token: free.to_string(),
+            meme_token: "0".to_owned(),
+            non_fungible_token: "0".to_owned(),
+        })
+    }
+
+    /// Read the storage at the key, of the given block or the best block.
+    async fn get_storage(&self, key: &[u8], block_hash: Option<&str>) -> Result<Option<Vec<u8>>> {
+        let key = format!("0x{}", hex::encode(key));
+        let storage: Option<String> = self
+            .request("state_getStorage", json!([key, block_hash]))
+            .await?;
+        storage.map(|x| decode_hex(&x)).transpose()
+    }
+}
+
+/// A WebSocket connection, whose event loop runs on its own thread until it closes.
+struct Connection {
+    out: ws::Sender,
+    shared: Arc<Shared>,
+}
+
+/// State of a connection which its event loop and the requests share.
+#[derive(Default)]
+struct Shared {
+    /// Where to send the connection once it is open, or why it failed to open.
+    opened: Mutex<Option<oneshot::Sender<Result<ws::Sender>>>>,
+    /// Where to send the response of each request, by its ID.
+    pending: Mutex<HashMap<u64, oneshot::Sender<Result<String>>>>,
+    closed: AtomicBool,
+}
+
+impl fmt::Debug for Connection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Connection")
+            .field("closed", &self.is_closed())
+            .finish()
+    }
+}
+
+impl Connection {
+    /// Connect to the URI, failing if the connection is not open before the timeout.
+    async fn open(uri: &str, timeout: Duration) -> Result<Self> {
+        let (opened, on_open) = oneshot::channel();
+        let shared = Arc::new(Shared::default());
+        *shared.opened.lock().expect("opened is poisoned") = Some(opened);
+
+        let event_loop = Arc::clone(&shared);
+        let url = uri.to_owned();
+        // `ws` blocks on its own event loop.
+        std::thread::spawn(move || {
+            let result = ws::connect(url, |out| RpcHandler {
+                out,
+                shared: Arc::clone(&event_loop),
+            });
+            event_loop.close(match result {
+                Ok(()) => "connection closed".to_owned(),
+                Err(e) => e.to_string(),
+            });
+        });
+
+        let out = tokio::time::timeout(timeout, on_open)
+            .await
+            .map_err(|_| Error::WebSocket(format!("failed to connect to {} in time", uri)))?
+            .map_err(|_| Error::WebSocket(format!("failed to connect to {}", uri)))??;
+        Ok(Self { out, shared })
+    }
+
+    fn is_closed(&self) -> bool {
+        self.shared.closed.load(Ordering::SeqCst)
+    }
+
+    /// Send the request of the ID and wait for its response.
+    async fn send(&self, id: u64, request: String, timeout: Duration) -> Result<String> {
+        let (sender, response) = oneshot::channel();
+        self.shared.pending().insert(id, sender);
+        // The connection may have closed and failed the pending requests before this one came.
+        if self.is_closed() {
+            self.shared.pending().remove(&id);
+            return Err(Error::WebSocket("connection closed".to_owned()));
+        }
+        if let Err(e) = self.out.send(request) {
+            self.shared.pending().remove(&id);
+            return Err(Error::WebSocket(e.to_string()));
+        }
+
+        match tokio::time::timeout(timeout, response).await {
+            Ok(Ok(response)) => response,
+            Ok(Err(_)) => Err(Error::WebSocket("connection closed".to_owned())),
+            Err(_) => {
+                self.shared.pending().remove(&id);
+                Err(Error::WebSocket("no response in time".to_owned()))
+            }
+        }
+    }
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        let _ = self.out.close(ws::CloseCode::Normal);
+    }
+}
+
+impl Shared {
+    fn pending(&self) -> MutexGuard<'_, HashMap<u64, oneshot::Sender<Result<String>>>> {
+        self.pending.lock().expect("pending is poisoned")
+    }
+
+    /// Mark the connection closed, failing whoever waits for it.
+    fn close(&self, reason: String) {
+        self.closed.store(true, Ordering::SeqCst);
+        if let Some(opened) = self.opened.lock().expect("opened is poisoned").take() {
+            let _ = opened.send(Err(Error::WebSocket(reason.clone())));
+        }
+        for (_, response) in self.pending().drain() {
+            let _ = response.send(Err(Error::WebSocket(reason.clone())));
+        }
+    }
+}
+
+struct RpcHandler {
+    out: ws::Sender,
+    shared: Arc<Shared>,
+}
+
+impl ws::Handler for RpcHandler {
+    fn on_open(&mut self, _: ws::Handshake) -> ws::Result<()> {
+        let opened = self
+            .shared
+            .opened
+            .lock()
+            .expect("opened is poisoned")
+            .take();
+        match opened.map(|x| x.send(Ok(self.out.clone()))) {
+            Some(Ok(())) => Ok(()),
+            // Nobody waits for the connection, since it opened too late.
+            _ => self.out.close(ws::CloseCode::Normal),
+        }
+    }
+
+    fn on_message(&mut self, msg: ws::Message) -> ws::Result<()> {
+        // Notifications of subscriptions have no ID, and are ignored.
+        let response = match msg.into_text() {
+            Ok(response) => response,
+            Err(_) => return Ok(()),
+        };
+        let id = serde_json::from_str::<Value>(&response)
+            .ok()
+            .and_then(|x| x["id"].as_u64());
+        if let Some(sender) = id.and_then(|id| self.shared.pending().remove(&id)) {
+            let _ = sender.send(Ok(response));
+        }
+        Ok(())
+    }
+
+    fn on_close(&mut self, _: ws::CloseCode, reason: &str) {
+        self.shared.close(format!("connection closed: {}", reason));
+    }
+
+    fn on_error(&mut self, err: ws::Error) {
+        self.shared.close(err.to_string());
+    }
+}
+
+/// Return the key of a storage item, followed by the hashed keys of a map.
+fn storage_key(pallet: &str, item: &str, map_key: &[u8]) -> Vec<u8> {
+    let mut key = twox_128(pallet.as_bytes()).to_vec();
+    key.extend(twox_128(item.as_bytes()));
+    key.extend(map_key);
+    key
+}
+
+fn twox_128(data: &[u8]) -> [u8; 16] {
+    let mut hash = [0; 16];
+    for (seed, chunk) in hash.chunks_mut(8).enumerate() {
+        let mut hasher = XxHash64::with_seed(seed as u64);
+        hasher.write(data);
+        chunk.copy_from_slice(&hasher.finish().to_le_bytes());
+    }
+    hash
+}
+
+fn blake2_128_concat(data: &[u8]) -> Vec<u8> {
+    let mut key = Blake2b::<U16>::digest(data).to_vec();
+    key.extend(data);
+    key
+}
+
+/// Return the account ID of an SS58 address of any format.
+pub fn decode_ss58(address: &str) -> Result<[u8; 32]> {
+    let invalid = |reason: &str| Error::InvalidAddress(format!("{}: {}", address, reason));
+    let data = bs58::decode(address)
+        .into_vec()
+        .map_err(|e| invalid(&e.to_string()))?;
+    // The format takes one byte below 64, and two bytes otherwise.
+    let prefix_len = match data.first() {
+        Some(0..=63) => 1,
+        Some(64..=127) => 2,
+        _ => return Err(invalid("unknown format")),
+    };
+    if data.len() != prefix_len + 32 + 2 {
+        return Err(invalid("not a 32 bytes account"));
+    }
+    let (payload, checksum) = data.split_at(prefix_len + 32);
+    let hash = Blake2b512::new()
+        .chain_update(b"SS58PRE")
+        .chain_update(payload)
+        .finalize();
+    if hash[..2] != *checksum {
+        return Err(invalid("invalid checksum"));
+    }
+
+    let mut account_id = [0; 32];
+    account_id.copy_from_slice(&payload[prefix_len..]);
+    Ok(account_id)
+}
+
+/// Return the slot or the authority index of the first `PreRuntime` digest of Aura or BABE.
+fn author_slot(logs: &[String]) -> Result<Option<AuthorSlot>> {
+    for log in logs {
+        let item = decode_hex(log)?;
+        if item.first() != Some(&DIGEST_PRE_RUNTIME) {
+            continue;
+        }
+        let (engine, data) = <([u8; 4], Vec<u8>)>::decode(&mut &item[1..])
+            .map_err(|e| Error::Scale(format!("invalid digest {}: {}", log, e)))?;
+        let slot = match &engine {
+            b"aura" => u64::decode(&mut data.as_slice()).map(AuthorSlot::Aura),
+            // Every variant of `PreDigest` begins with the authority index.
+            b"BABE" => u32::decode(&mut data.get(1..).unwrap_or_default()).map(AuthorSlot::Babe),
+            _ => continue,
+        };
+        return slot
+            .map(Some)
+            .map_err(|e| Error::Scale(format!("invalid digest {}: {}", log, e)));
+    }
+    Ok(None)
+}
+
+fn parse_hex_number(value: &str) -> Result<u64> {
+    u64::from_str_radix(value.trim_start_matches("0x"), 16)
+        .map_err(|e| Error::Scale(format!("invalid number {}: {}", value, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compute_storage_keys() {
+        assert_eq!(
+            hex::encode(storage_key("Timestamp", "Now", &[])),
+            "f0c365c3cf59d671eb72da0e7a4113c49f1f0515f462cdcf84e0f1d6045dfcbb"
+        );
+        let alice = decode_ss58("5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY").unwrap();
+        assert_eq!(
+            hex::encode(alice),
+            "d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d"
+        );
+        assert_eq!(
+            hex::encode(storage_key("System", "Account", &blake2_128_concat(&alice))),
+            "26aa394eea5630e07c48ae0c9558cef7b99d880ec681799c0cf30e8886371da9\
+             de1e86a9a8c739864cf3cc5ec2bea59fd43593c715fdd31c61141abd04a99fd6\
+             822c8558854ccde39a5684e7a56da27d"
+        );
+        assert_eq!(
+            hex::encode(storage_key("Session", "Validators", &[])),
+            "cec5070d609dd3497f72bde07fc96ba088dcde934c658227ee1dfafcd6e16903"
+        );
+        // The same account in the Astar format.
+        assert_eq!(
+            decode_ss58("ajYMsCKsEAhEvHpeA4XqsfiA9v1CdzZPrCfS6pEfeGHW9j8").unwrap(),
+            alice
+        );
+        assert!(decode_ss58("5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQZ").is_err());
+    }
+}
@@ -0,0 +1,278 @@
+//! SCALE encoding of the types which implement `Serialize` but not `Encode`,
+//! e.g. those of the common crates shared with the other colony chains.
+//!
+//! The bytes are those which `#[derive(Encode)]` gives for a type of the same shape,
+//! so contracts decode them into their own copies of the types deriving `Decode`:
+//! fields in order, sequences, maps and strings after their compact length,
+//! and enum variants after their index as `u8`.
+//! Types which serialize themselves as strings, e.g. `rust_decimal::Decimal`, are `String`s.
+
+use crate::*;
+use codec::{Compact, Encode};
+use serde::ser::{self, Serialize};
+use std::fmt::Display;
+
+/// Return the SCALE encoding of the value.
+pub fn to_vec<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>> {
+    let mut serializer = Serializer { output: Vec::new() };
+    value.serialize(&mut serializer)?;
+    Ok(serializer.output)
+}
+
+impl ser::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Error::Scale(msg.to_string())
+    }
+}
+
+struct Serializer {
+    output: Vec<u8>,
+}
+
+impl Serializer {
+    fn push(&mut self, value: impl Encode) -> Result<()> {
+        value.encode_to(&mut self.output);
+        Ok(())
+    }
+
+    fn push_len(&mut self, len: Option<usize>) -> Result<()> {
+        let len = len.ok_or_else(|| Error::Scale("the length is unknown".to_owned()))?;
+        let len = u32::try_from(len)
+            .map_err(|_| Error::Scale(format!("the length {} is too long", len)))?;
+        self.push(Compact(len))
+    }
+
+    fn push_variant(&mut self, index: u32) -> Result<()> {
+        let index = u8::try_from(index)
+            .map_err(|_| Error::Scale(format!("the variant {} is over 255", index)))?;
+        self.push(index)
+    }
+}
+
+impl ser::Serializer for &mut Serializer {
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Self;
+    type SerializeMap = Self;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Self;
+
+    fn serialize_bool(self, v: bool) -> Result<()> {
+        self.push(v)
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<()> {
+        self.push(v)
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<()> {
+        self.push(v)
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<()> {
+        self.push(v)
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<()> {
+        self.push(v)
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<()> {
+        self.push(v)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<()> {
+        self.push(v)
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<()> {
+        self.push(v)
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<()> {
+        self.push(v)
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<()> {
+        self.push(v)
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<()> {
+        self.push(v)
+    }
+
+    fn serialize_f32(self, _: f32) -> Result<()> {
+        Err(Error::Scale("SCALE has no floats".to_owned()))
+    }
+
+    fn serialize_f64(self, _: f64) -> Result<()> {
+        Err(Error::Scale("SCALE has no floats".to_owned()))
+    }
+
+    fn serialize_char(self, v: char) -> Result<()> {
+        self.push(v as u32)
+    }
+
+    fn serialize_str(self, v: &str) -> Result<()> {
+        self.push(v)
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<()> {
+        self.push(v)
+    }
+
+    fn serialize_none(self) -> Result<()> {
+        self.push(0u8)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<()> {
+        self.push(1u8)?;
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<()> {
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> Result<()> {
+        Ok(())
+    }
+
+    fn serialize_unit_variant(self, _: &'static str, index: u32, _: &'static str) -> Result<()> {
+        self.push_variant(index)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        index: u32,
+        _: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        self.push_variant(index)?;
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self> {
+        self.push_len(len)?;
+        Ok(self)
+    }
+
+    fn serialize_tuple(self, _: usize) -> Result<Self> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_struct(self, _: &'static str, _: usize) -> Result<Self> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        index: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self> {
+        self.push_variant(index)?;
+        Ok(self)
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self> {
+        self.push_len(len)?;
+        Ok(self)
+    }
+
+    fn serialize_struct(self, _: &'static str, _: usize) -> Result<Self> {
+        Ok(self)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        index: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self> {
+        self.push_variant(index)?;
+        Ok(self)
+    }
+}
+
+/// Implement the serializers of compound types, which encode their elements in order.
+macro_rules! impl_compound {
+    ($($trait:ident::$method:ident),* $(,)?) => {
+        $(impl ser::$trait for &mut Serializer {
+            type Ok = ();
+            type Error = Error;
+
+            fn $method<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+                value.serialize(&mut **self)
+            }
+
+            fn end(self) -> Result<()> {
+                Ok(())
+            }
+        })*
+    };
+}
+
+impl_compound!(
+    SerializeSeq::serialize_element,
+    SerializeTuple::serialize_element,
+    SerializeTupleStruct::serialize_field,
+    SerializeTupleVariant::serialize_field,
+);
+
+impl ser::SerializeMap for &mut Serializer {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<()> {
+        key.serialize(&mut **self)
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeStruct for &mut Serializer {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, _: &'static str, value: &T) -> Result<()> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeStructVariant for &mut Serializer {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, _: &'static str, value: &T) -> Result<()> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<()> {
+        Ok(())
+    }
+}
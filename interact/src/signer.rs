@@ -0,0 +1,113 @@
+use crate::*;
+use serde_json::Value;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// What the http server signs a transaction with.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum Credential {
+    /// A mnemonic or any secret URI, e.g. `//Alice`.
+    Mnemonic { mnemonic: String },
+    /// An encrypted keystore exported from polkadot.js, and its password.
+    Keystore { keystore: Value, password: String },
+}
+
+impl fmt::Debug for Credential {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Credential::Mnemonic { .. } => f.write_str("Mnemonic(..)"),
+            Credential::Keystore { keystore, .. } => {
+                write!(f, "Keystore({})", keystore["address"])
+            }
+        }
+    }
+}
+
+/// An account which signs the transactions sent through an `InteractClient`.
+pub trait Signer: Send + Sync {
+    /// Return the credential to give to the http server.
+    fn credential(&self) -> Result<Credential>;
+}
+
+/// Signs with a mnemonic kept in memory.
+#[derive(Clone)]
+pub struct MnemonicSigner {
+    mnemonic: String,
+}
+
+impl MnemonicSigner {
+    pub fn new(mnemonic: &str) -> Self {
+        Self {
+            mnemonic: mnemonic.to_owned(),
+        }
+    }
+}
+
+impl fmt::Debug for MnemonicSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("MnemonicSigner(..)")
+    }
+}
+
+impl Signer for MnemonicSigner {
+    fn credential(&self) -> Result<Credential> {
+        Ok(Credential::Mnemonic {
+            mnemonic: self.mnemonic.clone(),
+        })
+    }
+}
+
+/// Signs with an encrypted keystore file, which is read on every signature.
+#[derive(Clone)]
+pub struct KeystoreSigner {
+    path: PathBuf,
+    password: String,
+}
+
+impl KeystoreSigner {
+    pub fn new(path: impl AsRef<Path>, password: &str) -> Self {
+        Self {
+            path: path.as_ref().to_owned(),
+            password: password.to_owned(),
+        }
+    }
+}
+
+impl fmt::Debug for KeystoreSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "KeystoreSigner({})", self.path.display())
+    }
+}
+
+impl Signer for KeystoreSigner {
+    fn credential(&self) -> Result<Credential> {
+        let keystore = serde_json::from_slice(&std::fs::read(&self.path)?)?;
+        Ok(Credential::Keystore {
+            keystore,
+            password: self.password.clone(),
+        })
+    }
+}
+
+/// Signs with the mnemonic in an environment variable, which is read on every signature.
+#[derive(Debug, Clone)]
+pub struct EnvSigner {
+    var: String,
+}
+
+impl EnvSigner {
+    pub fn new(var: &str) -> Self {
+        Self {
+            var: var.to_owned(),
+        }
+    }
+}
+
+impl Signer for EnvSigner {
+    fn credential(&self) -> Result<Credential> {
+        let mnemonic = std::env::var(&self.var)
+            .map_err(|_| Error::MissingSigner(format!("{} is not set", self.var)))?;
+        Ok(Credential::Mnemonic { mnemonic })
+    }
+}
@@ -0,0 +1,167 @@
+//! A typed client of the `simple_counter` contract.
+
+use crate::*;
+use std::fmt;
+use std::str::FromStr;
+
+/// The messages of the contract which `SimpleCounterClient` calls, named as in the metadata.
+pub const MESSAGES: &[&str] = &[
+    "init",
+    "execute",
+    "add_auth",
+    "remove_auth",
+    "increment",
+    "decrement",
+    "reset",
+    "get_count",
+    "get_auth",
+];
+
+/// `Error` of the contract, which its messages return instead of reverting.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CounterError {
+    AlreadyInitialized,
+    NotInitialized,
+    WillBeZeroAuth,
+    NotAuthorized,
+    AlreadyRegistered,
+    AlreadyRemoved,
+    ValueIsOver10,
+    CallerNotAuth,
+}
+
+impl FromStr for CounterError {
+    type Err = Error;
+
+    /// Parse the reason given by the http server, which is the name of the variant.
+    fn from_str(s: &str) -> Result<Self> {
+        serde_json::from_value(serde_json::Value::String(s.to_owned()))
+            .map_err(|_| Error::Backend(format!("unknown simple_counter error {}", s)))
+    }
+}
+
+impl fmt::Display for CounterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+/// An error of a call to the contract.
+#[derive(thiserror::Error, Debug)]
+pub enum CallError {
+    /// When the contract rejects the message.
+    #[error("simple_counter rejected the message: {0}")]
+    Contract(CounterError),
+    #[error(transparent)]
+    Interact(Error),
+}
+
+impl From<Error> for CallError {
+    fn from(error: Error) -> Self {
+        match error {
+            Error::ContractReverted(reason) => match reason.parse() {
+                Ok(error) => CallError::Contract(error),
+                Err(_) => CallError::Interact(Error::ContractReverted(reason)),
+            },
+            error => CallError::Interact(error),
+        }
+    }
+}
+
+pub type CallResult<T> = std::result::Result<T, CallError>;
+
+/// A client of a deployed `simple_counter`, signing its transactions with the given signer.
+pub struct SimpleCounterClient<'a> {
+    client: &'a InteractClient,
+    signer: &'a dyn Signer,
+    contract: Contract,
+}
+
+impl<'a> SimpleCounterClient<'a> {
+    pub fn new(client: &'a InteractClient, signer: &'a dyn Signer, address: &str) -> Self {
+        Self {
+            client,
+            signer,
+            contract: Contract::new(Contract::SIMPLE_COUNTER).at(address),
+        }
+    }
+
+    pub fn contract(&self) -> &Contract {
+        &self.contract
+    }
+
+    async fn send(&self, method_name: &str, arguments: Vec<Argument>) -> CallResult<ContractTx> {
+        Ok(self
+            .client
+            .execute_contract_method(self.signer, &self.contract, method_name, arguments)
+            .await?)
+    }
+
+    async fn query(&self, field: &str) -> Result<Vec<String>> {
+        let query = self
+            .client
+            .query_contract_state(&self.contract, field, Vec::new())
+            .await?;
+        Ok(query.output)
+    }
+
+    /// Set the count and the first auth, once.
+    pub async fn init(&self, init_count: u64, auth: AccountId) -> CallResult<ContractTx> {
+        self.send("init", vec![init_count.into(), auth.into()])
+            .await
+    }
+
+    /// Add the input, at most 10, to the count and return it.
+    pub async fn execute(&self, input: u64) -> CallResult<u64> {
+        let tx = self.send("execute", vec![input.into()]).await?;
+        let value = tx
+            .events
+            .iter()
+            .find(|x| x.name == "Transaction")
+            .and_then(|x| x.args.first())
+            .ok_or_else(|| Error::Backend("no Transaction event".to_owned()))?;
+        Ok(parse_u64(value)?)
+    }
+
+    pub async fn add_auth(&self, new_auth: AccountId) -> CallResult<ContractTx> {
+        self.send("add_auth", vec![new_auth.into()]).await
+    }
+
+    pub async fn remove_auth(&self, auth: AccountId) -> CallResult<ContractTx> {
+        self.send("remove_auth", vec![auth.into()]).await
+    }
+
+    pub async fn increment(&self) -> CallResult<ContractTx> {
+        self.send("increment", Vec::new()).await
+    }
+
+    pub async fn decrement(&self) -> CallResult<ContractTx> {
+        self.send("decrement", Vec::new()).await
+    }
+
+    pub async fn reset(&self) -> CallResult<ContractTx> {
+        self.send("reset", Vec::new()).await
+    }
+
+    pub async fn get_count(&self) -> Result<u64> {
+        let output = self.query("count").await?;
+        parse_u64(output.first().map(|x| x.as_str()).unwrap_or_default())
+    }
+
+    pub async fn get_auth(&self) -> Result<Vec<AccountId>> {
+        self.query("auth")
+            .await?
+            .iter()
+            .map(|x| x.parse())
+            .collect()
+    }
+}
+
+/// Parse a number of the http server, which gives the large ones in hex.
+fn parse_u64(value: &str) -> Result<u64> {
+    match value.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16),
+        None => value.parse(),
+    }
+    .map_err(|e| Error::Backend(format!("invalid number {}: {}", value, e)))
+}
@@ -0,0 +1,137 @@
+//! Tracking a submitted transaction until it is included in a block or finalized.
+
+use crate::*;
+use serde_json::json;
+use std::time::Duration;
+
+/// How far a transaction has gone.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum TxStatus {
+    InBlock,
+    Finalized,
+}
+
+/// The block which includes a transaction, and how the runtime dispatched it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TxInclusion {
+    pub block_hash: String,
+    pub block_number: u64,
+    pub extrinsic_index: u32,
+    /// Why the runtime failed to dispatch the transaction, e.g. `contracts.ContractTrapped`.
+    pub dispatch_error: Option<String>,
+    /// Fee paid by the signer, in planck.
+    pub fee: String,
+    /// Every event of the transaction, including the failed ones.
+    pub events: Vec<TxEvent>,
+    pub finalized: bool,
+}
+
+impl TxInclusion {
+    pub fn status(&self) -> TxStatus {
+        if self.finalized {
+            TxStatus::Finalized
+        } else {
+            TxStatus::InBlock
+        }
+    }
+}
+
+/// A runtime event, e.g. `balances.Transfer`, with its arguments as in `ContractEvent`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TxEvent {
+    pub section: String,
+    pub method: String,
+    pub data: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Inclusion {
+    pub inclusion: Option<TxInclusion>,
+    /// Height to look from in the next poll, above the blocks which need not be scanned again.
+    #[serde(default)]
+    pub resume_height: Option<u64>,
+}
+
+/// A submitted transaction, which is looked for in the blocks from the height it was submitted at.
+#[derive(Debug, Clone)]
+pub struct TxHandle {
+    client: InteractClient,
+    tx_hash: String,
+    from_height: u64,
+    poll_interval: Duration,
+}
+
+impl TxHandle {
+    pub fn new(client: InteractClient, tx_hash: &str, from_height: u64) -> Self {
+        Self {
+            client,
+            tx_hash: tx_hash.to_owned(),
+            from_height,
+            poll_interval: Duration::from_secs(2),
+        }
+    }
+
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    pub fn tx_hash(&self) -> &str {
+        &self.tx_hash
+    }
+
+    /// Return the block which includes the transaction, if any yet.
+    pub async fn inclusion(&self) -> Result<Option<TxInclusion>> {
+        Ok(self.inclusion_from(self.from_height).await?.inclusion)
+    }
+
+    async fn inclusion_from(&self, from_height: u64) -> Result<Inclusion> {
+        let path = "tx/inclusion";
+        let data = json!({
+            "fullNodeUri": self.client.config().full_node_uri,
+            "txHash": self.tx_hash,
+            "fromHeight": from_height,
+        });
+        self.client.get_response(path, data).await
+    }
+
+    /// Wait until the transaction reaches the status, or fail after the deadline.
+    /// A transaction which the runtime failed to dispatch is still returned,
+    /// with its `dispatch_error`.
+    pub async fn wait(&self, status: TxStatus, deadline: Duration) -> Result<TxInclusion> {
+        let wait = async {
+            let mut from_height = self.from_height;
+            loop {
+                let Inclusion {
+                    inclusion,
+                    resume_height,
+                } = self.inclusion_from(from_height).await?;
+                match inclusion {
+                    Some(inclusion) if inclusion.status() >= status => return Ok(inclusion),
+                    _ => {
+                        // Each poll scans only the blocks which the last one did not settle.
+                        from_height = resume_height.unwrap_or(from_height);
+                        tokio::time::sleep(self.poll_interval).await
+                    }
+                }
+            }
+        };
+        tokio::time::timeout(deadline, wait).await.map_err(|_| {
+            Error::TxTimeout(format!(
+                "{} is not {:?} after {:?}",
+                self.tx_hash, status, deadline
+            ))
+        })?
+    }
+
+    pub async fn wait_in_block(&self, deadline: Duration) -> Result<TxInclusion> {
+        self.wait(TxStatus::InBlock, deadline).await
+    }
+
+    pub async fn wait_finalized(&self, deadline: Duration) -> Result<TxInclusion> {
+        self.wait(TxStatus::Finalized, deadline).await
+    }
+}
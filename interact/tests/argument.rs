@@ -0,0 +1,96 @@
+use codec::Encode;
+use pdao_polkadot_interact::*;
+use serde::Serialize;
+use serde_json::json;
+use std::collections::BTreeMap;
+
+#[derive(Encode)]
+struct Transfer {
+    receiver: AccountId,
+    amount: u128,
+}
+
+/// Encode the arguments with the names of their types in the metadata.
+#[test]
+fn encode_arguments() {
+    assert_eq!(
+        serde_json::to_value(Argument::from(5u64)).unwrap(),
+        json!({ "type": "u64", "value": "0x0500000000000000" })
+    );
+    assert_eq!(
+        Argument::from(vec![1u8, 2, 3]),
+        Argument {
+            type_name: "Vec".to_owned(),
+            value: "0x0c010203".to_owned(),
+        }
+    );
+
+    let alice: AccountId = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY"
+        .parse()
+        .unwrap();
+    let argument = Argument::from(alice);
+    assert_eq!(argument.type_name, "AccountId");
+    assert_eq!(
+        argument.value,
+        "0xd43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d"
+    );
+    assert!("5".parse::<AccountId>().is_err());
+
+    let argument = Argument::custom(
+        "Transfer",
+        &Transfer {
+            receiver: alice,
+            amount: 1,
+        },
+    );
+    assert_eq!(argument.encoded().unwrap().len(), 32 + 16);
+}
+
+#[derive(Encode, Serialize)]
+struct Message {
+    sequence: u64,
+    receiver: AccountId32,
+    amount: u128,
+    memo: String,
+    data: Vec<u8>,
+    limit: Option<u32>,
+    kind: Kind,
+    tags: BTreeMap<String, (bool, i16)>,
+}
+
+#[derive(Encode, Serialize)]
+struct AccountId32([u8; 32]);
+
+#[derive(Encode, Serialize)]
+enum Kind {
+    Fungible,
+    NonFungible { collection: String, index: u64 },
+}
+
+/// Encode the types without `Encode` as `#[derive(Encode)]` would.
+#[test]
+fn encode_serialized_arguments() {
+    let mut message = Message {
+        sequence: 3,
+        receiver: AccountId32([7; 32]),
+        amount: u128::MAX,
+        memo: "pdao".to_owned(),
+        data: vec![1, 2, 3],
+        limit: Some(10),
+        kind: Kind::NonFungible {
+            collection: "punks".to_owned(),
+            index: 9,
+        },
+        tags: [("a".to_owned(), (true, -1)), ("b".to_owned(), (false, 2))]
+            .into_iter()
+            .collect(),
+    };
+    let argument = Argument::serialized("Message", &message).unwrap();
+    assert_eq!(argument.type_name, "Message");
+    assert_eq!(argument.encoded().unwrap(), message.encode());
+
+    message.limit = None;
+    message.kind = Kind::Fungible;
+    assert_eq!(scale::to_vec(&message).unwrap(), message.encode());
+    assert!(scale::to_vec(&1.5f64).is_err());
+}
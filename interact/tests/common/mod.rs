@@ -0,0 +1,16 @@
+//! Fixtures shared by the integration tests.
+
+use std::path::PathBuf;
+
+/// Return a path in the temporary directory for the file of the test,
+/// unique to this process and removed if a previous run left it behind.
+pub fn temp_path(test: &str, extension: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!(
+        "pdao_polkadot_interact_test-{}-{}.{}",
+        std::process::id(),
+        test,
+        extension
+    ));
+    let _ = std::fs::remove_file(&path);
+    path
+}
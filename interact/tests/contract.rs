@@ -0,0 +1,32 @@
+use pdao_polkadot_interact::*;
+
+/// Parse contract descriptors and write them back.
+#[test]
+fn parse_contract_descriptors() {
+    let contract: Contract = "simple_counter".parse().unwrap();
+    assert_eq!(contract, Contract::new("simple_counter"));
+    assert!(contract.address().is_err());
+
+    let descriptor = "my_token@5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY\
+                      ?metadata=contracts/my_token.contract&code_hash=0x6ecc";
+    let contract: Contract = descriptor.parse().unwrap();
+    assert_eq!(
+        contract,
+        Contract::new("my_token")
+            .at("5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY")
+            .with_metadata("contracts/my_token.contract")
+            .with_code_hash("0x6ecc")
+    );
+    assert_eq!(contract.to_string(), descriptor);
+    assert_eq!(
+        serde_json::to_value(&contract).unwrap(),
+        serde_json::Value::String(descriptor.to_owned())
+    );
+
+    for invalid in ["", "@5Grw", "my token", "my_token@", "my_token?salt=0"] {
+        match invalid.parse::<Contract>() {
+            Err(Error::InvalidContract(_)) => (),
+            other => panic!("unexpected result of {}: {:?}", invalid, other),
+        }
+    }
+}
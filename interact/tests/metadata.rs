@@ -0,0 +1,92 @@
+use pdao_polkadot_interact::metadata::*;
+use pdao_polkadot_interact::*;
+
+fn simple_counter() -> ContractMetadata {
+    ContractMetadata::from_file(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../deno-http-server/contracts/simple_counter.contract"
+    ))
+    .unwrap()
+}
+
+/// Read the constructors, messages, events and types of the bundle.
+#[test]
+fn read_simple_counter_metadata() {
+    let metadata = simple_counter();
+    assert_eq!(
+        metadata.source.hash,
+        "0x6ecc2b0982a0bae1c7b610d242af92b10e19ead468bc2c464e0108962e65d369"
+    );
+    assert_eq!(&metadata.wasm().unwrap()[..4], b"\0asm");
+
+    let constructor = metadata.constructor("new").unwrap();
+    assert_eq!(constructor.selector().unwrap(), [0x9b, 0xae, 0x9d, 0x5e]);
+    assert_eq!(constructor.args[0].type_spec.name(), "u64");
+
+    let labels: Vec<_> = metadata
+        .spec
+        .messages
+        .iter()
+        .map(|x| x.label.as_str())
+        .collect();
+    assert_eq!(
+        labels,
+        vec![
+            "init",
+            "only_auth",
+            "execute",
+            "add_auth",
+            "remove_auth",
+            "increment",
+            "decrement",
+            "reset",
+            "get_count",
+            "get_auth"
+        ]
+    );
+    let get_count = metadata.message("get_count").unwrap();
+    assert!(!get_count.mutates);
+    assert_eq!(get_count.return_type.as_ref().unwrap().name(), "u64");
+    assert!(metadata.message("increment").unwrap().return_type.is_none());
+
+    let add_auth = metadata.message("add_auth").unwrap();
+    let account_id = add_auth.args[0].type_spec.id;
+    assert_eq!(
+        metadata.type_of(account_id).unwrap().path,
+        vec!["ink_env", "types", "AccountId"]
+    );
+    assert_eq!(metadata.encoded_size(account_id), Some(32));
+
+    let event = metadata.event("Transaction").unwrap();
+    assert_eq!(event.args[0].type_spec.name(), "u64");
+}
+
+/// Reject the calls which do not match the metadata.
+#[test]
+fn validate_calls() {
+    let metadata = simple_counter();
+    let alice: AccountId = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY"
+        .parse()
+        .unwrap();
+
+    metadata
+        .validate_constructor("new", &[5u64.into()])
+        .unwrap();
+    metadata
+        .validate_message("init", &[5u64.into(), alice.into()])
+        .unwrap();
+
+    let invalid_calls = vec![
+        ("incremnet", vec![]),
+        ("execute", vec![]),
+        ("add_auth", vec![Argument::from(5u64)]),
+        // The right type name, but not a 32 bytes account.
+        ("add_auth", vec![Argument::custom("AccountId", &5u64)]),
+    ];
+    for (label, arguments) in invalid_calls {
+        match metadata.validate_message(label, &arguments) {
+            Err(Error::InvalidCall(_)) => (),
+            other => panic!("unexpected result of {}: {:?}", label, other),
+        }
+    }
+}
@@ -0,0 +1,58 @@
+mod common;
+
+use pdao_polkadot_interact::registry::*;
+use pdao_polkadot_interact::*;
+
+/// Append deployments and read them back from the registry file.
+#[test]
+fn append_and_reopen_registry() {
+    let path = common::temp_path("append_and_reopen_registry", "json");
+    let deploy = ContractDeploy {
+        contract_name: "simple_counter".to_owned(),
+        contract_addr: "aeQtHkL7ENgfVYwiMZNYgz2GeRa75B2cSaVz1AFjZ9omRtv".to_owned(),
+        code_hash: "0x6ecc2b0982a0bae1c7b610d242af92b10e19ead468bc2c464e0108962e65d369".to_owned(),
+        tx_hash: "0x1234".to_owned(),
+        block_number: 100,
+    };
+
+    let mut registry = DeploymentRegistry::open(&path).unwrap();
+    registry
+        .append(Deployment::new(
+            Contract::new(Contract::SIMPLE_COUNTER),
+            &deploy,
+        ))
+        .unwrap();
+    registry
+        .append(Deployment::new(Contract::new(Contract::TREASURY), &deploy))
+        .unwrap();
+
+    let registry = DeploymentRegistry::open(&path).unwrap();
+    assert_eq!(registry.deployments().len(), 2);
+    let treasury = registry.find(Contract::TREASURY).unwrap();
+    assert_eq!(treasury.block_number, 100);
+    assert_eq!(treasury.contract_addr(), deploy.contract_addr);
+    assert_eq!(treasury.contract().name, Contract::TREASURY);
+    assert!(registry.find(Contract::LIGHT_CLIENT).is_none());
+    std::fs::remove_file(&path).unwrap();
+}
+
+/// Reject the deployments which are not at an address.
+#[test]
+fn reject_deployments_without_addresses() {
+    let deployment = serde_json::json!({
+        "contract": "simple_counter",
+        "code_hash": "0x6ecc",
+        "tx_hash": "0x1234",
+        "block_number": 100,
+    });
+    assert!(serde_json::from_value::<Deployment>(deployment.clone()).is_err());
+
+    let mut deployment = deployment;
+    deployment["contract"] =
+        "simple_counter@aeQtHkL7ENgfVYwiMZNYgz2GeRa75B2cSaVz1AFjZ9omRtv".into();
+    let deployment: Deployment = serde_json::from_value(deployment).unwrap();
+    assert_eq!(
+        deployment.contract_addr(),
+        "aeQtHkL7ENgfVYwiMZNYgz2GeRa75B2cSaVz1AFjZ9omRtv"
+    );
+}
@@ -0,0 +1,37 @@
+use pdao_polkadot_interact::{ContractQuery, Error, Response};
+use serde_json::json;
+
+fn decode(body: serde_json::Value) -> Result<ContractQuery, Error> {
+    serde_json::from_value::<Response<ContractQuery>>(body)
+        .unwrap()
+        .into_result()
+}
+
+/// Return the data of successful responses and the reported error of failed ones.
+#[test]
+fn decode_response_envelope() {
+    let query = decode(json!({
+        "success": true,
+        "data": {
+            "contractName": "simple_counter",
+            "messageName": "get",
+            "messageType": "query",
+            "output": ["5"],
+        },
+    }))
+    .unwrap();
+    assert_eq!(query.output, vec!["5".to_owned()]);
+
+    match decode(json!({ "success": true })) {
+        Err(Error::Backend(_)) => (),
+        other => panic!("unexpected result: {:?}", other),
+    }
+    match decode(json!({ "success": false, "msg": "unknown contract" })) {
+        Err(Error::Backend(msg)) => assert_eq!(msg, "unknown contract"),
+        other => panic!("unexpected result: {:?}", other),
+    }
+    match decode(json!({ "success": false, "msg": "reverted", "revert": "InvalidProof" })) {
+        Err(Error::ContractReverted(reason)) => assert_eq!(reason, "InvalidProof"),
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
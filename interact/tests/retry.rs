@@ -0,0 +1,56 @@
+use pdao_polkadot_interact::*;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::Duration;
+
+/// Double the backoff after each retry, up to the limit.
+#[test]
+fn compute_backoff() {
+    let policy = RetryPolicy {
+        initial_backoff_ms: 100,
+        max_backoff_ms: 1000,
+        ..RetryPolicy::default()
+    };
+    let backoffs: Vec<_> = (1..=6).map(|x| policy.backoff(x)).collect();
+    assert_eq!(
+        backoffs,
+        [100, 200, 400, 800, 1000, 1000].map(Duration::from_millis)
+    );
+    assert_eq!(policy.backoff(100), Duration::from_millis(1000));
+}
+
+/// Retry the reads after any retryable failure, but the transactions only if never sent.
+#[tokio::test]
+async fn retry_reads_and_unsent_transactions() {
+    let policy = RetryPolicy {
+        max_attempts: 3,
+        initial_backoff_ms: 1,
+        ..RetryPolicy::default()
+    };
+    let run = |idempotent: bool, error: fn() -> Error| {
+        let policy = policy.clone();
+        async move {
+            let attempts = AtomicU32::new(0);
+            let result: Result<()> = policy
+                .run(idempotent, || {
+                    attempts.fetch_add(1, Ordering::Relaxed);
+                    async { Err(error()) }
+                })
+                .await;
+            assert!(result.is_err());
+            attempts.load(Ordering::Relaxed)
+        }
+    };
+
+    let timeout = || Error::WebSocket("request timed out".to_owned());
+    assert_eq!(run(true, timeout).await, 3);
+    assert_eq!(run(false, timeout).await, 1);
+    let reverted = || Error::ContractReverted("ValueIsOver10".to_owned());
+    assert_eq!(run(true, reverted).await, 1);
+
+    // Nothing listens on the port, so the connection is refused.
+    let client = reqwest::Client::new();
+    let refused = client.get("http://127.0.0.1:9/").send().await.unwrap_err();
+    let refused = Error::from(refused);
+    assert_eq!(refused.class(), Some(ErrorClass::Connect));
+    assert!(policy.should_retry(&refused, false));
+}
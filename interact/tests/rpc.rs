@@ -0,0 +1,443 @@
+use codec::Encode;
+use futures::{StreamExt, TryStreamExt};
+use pdao_polkadot_interact::*;
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc};
+use std::time::Duration;
+
+const BLOCK_HASH: &str = "0x6a1f1fb7e1a4c96f0cd0cd5c4b9ca9f34a22e8b9c8b0d5f0e1a3c1c2d3e4f5a6";
+/// Key of `Timestamp.Now`.
+const TIMESTAMP_KEY: &str = "0xf0c365c3cf59d671eb72da0e7a4113c49f1f0515f462cdcf84e0f1d6045dfcbb";
+
+/// Code hash of `simple_counter` which is on the chain.
+const CODE_HASH: &str = "0x9f2f2f5b2b6e13f9e0fd7e1b6e1dc0b2a2d3b6f4b9c1e0f1a2b3c4d5e6f7a8b9";
+/// Key of `Session.Validators`.
+const VALIDATORS_KEY: &str = "0xcec5070d609dd3497f72bde07fc96ba088dcde934c658227ee1dfafcd6e16903";
+
+/// Return the hash of the block at the height, which is the height itself below the best block.
+fn block_hash(height: u64) -> String {
+    if height == 1234 {
+        BLOCK_HASH.to_owned()
+    } else {
+        format!("0x{:064x}", height)
+    }
+}
+
+/// Return the header of the block at the height, authored by Aura at the slot of the height.
+fn header(height: u64) -> Value {
+    // `DigestItem::PreRuntime(*b"aura", slot.encode())`
+    let pre_runtime = (6u8, *b"aura", height.encode()).encode();
+    json!({
+        "parentHash": block_hash(height - 1),
+        "number": format!("0x{:x}", height),
+        "stateRoot": format!("0x{:064x}", height + 1_000_000),
+        "extrinsicsRoot": format!("0x{:064x}", height + 2_000_000),
+        "digest": { "logs": [format!("0x{}", hex::encode(pre_runtime))] },
+    })
+}
+
+/// Respond to a JSON-RPC request as a full node at height 1234.
+fn respond(request: &Value) -> Value {
+    let result = match request["method"].as_str().unwrap() {
+        "chain_getHeader" => header(1234),
+        "chain_getBlockHash" => match request["params"][0].as_u64().unwrap() {
+            height @ 1..=1234 => json!(block_hash(height)),
+            _ => Value::Null,
+        },
+        "chain_getBlock" => {
+            let hash = request["params"][0].as_str().unwrap();
+            let height = match hash {
+                BLOCK_HASH => 1234,
+                _ => u64::from_str_radix(hash.trim_start_matches("0x"), 16).unwrap(),
+            };
+            // As many extrinsics as the height modulo 3, after the timestamp.
+            json!({
+                "block": {
+                    "header": header(height),
+                    "extrinsics": vec!["0x00"; 1 + height as usize % 3],
+                },
+                "justifications": null,
+            })
+        }
+        "state_getStorage" if request["params"][0] == TIMESTAMP_KEY => {
+            assert_eq!(request["params"][1], BLOCK_HASH);
+            json!(format!("0x{}", hex::encode(1_660_000_000_000u64.encode())))
+        }
+        "state_getStorage" if request["params"][0] == VALIDATORS_KEY => {
+            let validators = vec![[1u8; 32], [2u8; 32], [3u8; 32]];
+            json!(format!("0x{}", hex::encode(validators.encode())))
+        }
+        "state_getStorage" => {
+            // nonce, consumers, providers, sufficients, free, reserved, misc frozen, fee frozen
+            let mut info = (3u32, 0u32, 1u32, 0u32, 1_500u128, 500u128, 0u128, 0u128);
+            // The account `[0xff; 32]` has a balance which overflows.
+            if request["params"][0]
+                .as_str()
+                .unwrap()
+                .ends_with(&"ff".repeat(32))
+            {
+                info.4 = u128::MAX;
+            }
+            json!(format!("0x{}", hex::encode(info.encode())))
+        }
+        "contracts_call" => {
+            let call = &request["params"][0];
+            let input =
+                hex::decode(call["inputData"].as_str().unwrap().trim_start_matches("0x")).unwrap();
+            let result = match input.len() {
+                // `PSP34::owner_of`, of which only `Id::U64(7)` exists and is Alice's.
+                _ if input.starts_with(&[0x11, 0x68, 0x62, 0x4d]) => {
+                    let owner = (input[4..] == psp34::Id::U64(7).encode()).then(|| {
+                        "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY"
+                            .parse::<AccountId>()
+                            .unwrap()
+                    });
+                    json!({ "Ok": { "flags": 0, "data": format!("0x{}", hex::encode(owner.encode())) } })
+                }
+                // `get_count`
+                4 => {
+                    json!({ "Ok": { "flags": 0, "data": format!("0x{}", hex::encode(5u64.encode())) } })
+                }
+                // `execute`, which returns `Err(ValueIsOver10)` over 10.
+                12 if input[4] > 10 => json!({ "Ok": { "flags": 0, "data": "0x0106" } }),
+                12 => {
+                    json!({ "Ok": { "flags": 0, "data": format!("0x00{}", hex::encode(&input[4..])) } })
+                }
+                _ => json!({ "Err": { "Module": { "index": 70, "error": 11 } } }),
+            };
+            json!({
+                "gasConsumed": 1_500_000_000u64,
+                "gasRequired": "0x77359400",
+                "storageDeposit": { "charge": 0 },
+                "debugMessage": "0x",
+                "result": result,
+            })
+        }
+        "contracts_instantiate" => {
+            let instantiate = &request["params"][0];
+            // Values above `u64::MAX` are given in hex.
+            assert!(instantiate["value"].as_str().unwrap().starts_with("0x"));
+            let code = &instantiate["code"];
+            let (code_hash, wasm) = (code["existing"].as_str(), code["upload"].as_str());
+            assert!(code_hash.is_some() != wasm.is_some());
+            // The selector of `new` and `init_count`.
+            let data = instantiate["data"].as_str().unwrap();
+            assert_eq!(data.len(), 2 + 2 * (4 + 8));
+            let result = match code_hash {
+                Some(code_hash) if code_hash != CODE_HASH => {
+                    json!({ "Err": { "Module": { "index": 70, "error": 7 } } })
+                }
+                _ => json!({
+                    "Ok": {
+                        "result": { "flags": 0, "data": "0x" },
+                        "accountId": "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY",
+                    }
+                }),
+            };
+            json!({
+                // Newer nodes give the weight of two dimensions.
+                "gasConsumed": { "refTime": 4_000_000_000u64, "proofSize": 0 },
+                "gasRequired": { "refTime": "0x12a05f200", "proofSize": 0 },
+                // Uploading the code takes a deposit for it too.
+                "storageDeposit": { "charge": if wasm.is_some() { "0x8ac7230489e80000" } else { "0x0de0b6b3a7640000" } },
+                "debugMessage": "0x",
+                "result": result,
+            })
+        }
+        _ => {
+            return json!({
+                "jsonrpc": "2.0",
+                "id": request["id"],
+                "error": { "code": -32601, "message": "Method not found" },
+            })
+        }
+    };
+    json!({ "jsonrpc": "2.0", "id": request["id"], "result": result })
+}
+
+struct MockFullNode {
+    out: ws::Sender,
+}
+
+impl ws::Handler for MockFullNode {
+    fn on_message(&mut self, msg: ws::Message) -> ws::Result<()> {
+        let request: Value = serde_json::from_str(msg.as_text()?).unwrap();
+        if request["method"] == "mock_close" {
+            return self.out.close(ws::CloseCode::Away);
+        }
+        self.out.send(respond(&request).to_string())
+    }
+}
+
+/// Run a mock full node, and return its URI and the number of the connections it accepted.
+fn run_mock_full_node() -> (String, Arc<AtomicUsize>) {
+    let connections = Arc::new(AtomicUsize::new(0));
+    let accepted = Arc::clone(&connections);
+    let (address, bound) = mpsc::channel();
+    std::thread::spawn(move || {
+        let server = ws::WebSocket::new(move |out| {
+            accepted.fetch_add(1, Ordering::SeqCst);
+            MockFullNode { out }
+        })
+        .unwrap()
+        .bind("127.0.0.1:0")
+        .unwrap();
+        address.send(server.local_addr().unwrap()).unwrap();
+        server.run().unwrap();
+    });
+    (format!("ws://{}", bound.recv().unwrap()), connections)
+}
+
+/// Read the chain from the full node, without the http server.
+#[tokio::test]
+async fn read_through_json_rpc() {
+    let (full_node_uri, connections) = run_mock_full_node();
+    // Nothing listens on the http server, so every read must go through JSON-RPC.
+    let mut config = ClientConfig::new(&full_node_uri, "http://127.0.0.1:9/");
+    config.read_backend = ReadBackend::JsonRpc;
+    let client = InteractClient::new(config).unwrap();
+
+    let height = client.get_current_height().await.unwrap();
+    assert_eq!(height, 1234);
+
+    let block = client.get_block(height).await.unwrap();
+    assert_eq!(block.block_hash, BLOCK_HASH);
+    assert_eq!(block.timestamp, 1_660_000_000_000);
+
+    let account = client
+        .query_account("5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY")
+        .await
+        .unwrap();
+    assert_eq!(account.native_token, "2000");
+    assert_eq!(account.free_native_token, "1500");
+
+    match client.get_block(height + 1).await {
+        Err(Error::Rpc { .. }) => (),
+        other => panic!("unexpected result: {:?}", other),
+    }
+    match client
+        .query_account("5HrN7fHLXWcFiXPwwtq2EkSGns9eMt5P7SpeTPewumZy6ftb")
+        .await
+    {
+        Err(Error::Scale(_)) => (),
+        other => panic!("unexpected result: {:?}", other),
+    }
+    match client.query_account("not an address").await {
+        Err(Error::InvalidAddress(_)) => (),
+        other => panic!("unexpected result: {:?}", other),
+    }
+    assert_eq!(connections.load(Ordering::SeqCst), 1);
+
+    // A closed connection fails its requests, and the next request opens another.
+    let rpc =
+        RpcClient::new(&full_node_uri, Duration::from_secs(5)).with_retry(RetryPolicy::never());
+    match rpc.request::<Value>("mock_close", json!([])).await {
+        Err(Error::WebSocket(_)) => (),
+        other => panic!("unexpected result: {:?}", other),
+    }
+    assert_eq!(rpc.get_current_height().await.unwrap(), 1234);
+    assert_eq!(connections.load(Ordering::SeqCst), 3);
+}
+
+/// Simulate contract calls, decoding what they return.
+#[tokio::test]
+async fn dry_run_contract_calls() {
+    let (full_node_uri, _) = run_mock_full_node();
+    let metadata = ContractMetadata::from_file(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../deno-http-server/contracts/simple_counter.contract"
+    ))
+    .unwrap();
+    let client = InteractClient::new(ClientConfig::new(&full_node_uri, "http://127.0.0.1:9/"))
+        .unwrap()
+        .with_metadata(Contract::SIMPLE_COUNTER, metadata);
+    let alice = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY";
+    let counter = Contract::new(Contract::SIMPLE_COUNTER).at(alice);
+
+    let dry_run = client
+        .dry_run_call(alice, &counter, "get_count", &[], 0)
+        .await
+        .unwrap();
+    assert_eq!(dry_run.return_value, Some(json!(5)));
+    assert_eq!(dry_run.gas_consumed, 1_500_000_000);
+    assert_eq!(dry_run.gas_required, 2_000_000_000);
+    assert_eq!(dry_run.storage_deposit, StorageDeposit::Charge(0));
+    assert!(dry_run.is_ok());
+
+    let dry_run = client
+        .dry_run_call(alice, &counter, "execute", &[3u64.into()], 0)
+        .await
+        .unwrap();
+    assert_eq!(dry_run.return_value, Some(json!({ "Ok": 3 })));
+
+    let dry_run = client
+        .dry_run_call(alice, &counter, "execute", &[11u64.into()], 0)
+        .await
+        .unwrap();
+    assert_eq!(dry_run.revert.as_deref(), Some("ValueIsOver10"));
+    match dry_run.check() {
+        Err(Error::ContractReverted(reason)) => assert_eq!(reason, "ValueIsOver10"),
+        other => panic!("unexpected result: {:?}", other),
+    }
+
+    let dry_run = client
+        .dry_run_call(
+            alice,
+            &counter,
+            "init",
+            &[1u64.into(), AccountId([1; 32]).into()],
+            0,
+        )
+        .await
+        .unwrap();
+    assert!(dry_run.return_value.is_none());
+    assert!(dry_run.dispatch_error.unwrap().contains("Module"));
+
+    // Without metadata, the selector is unknown.
+    match client
+        .dry_run_call(alice, &Contract::new("unknown").at(alice), "get", &[], 0)
+        .await
+    {
+        Err(Error::InvalidCall(_)) => (),
+        other => panic!("unexpected result: {:?}", other),
+    }
+
+    // Collections are read without their metadata.
+    let owner = client
+        .psp34_owner_of(alice, &r#"{"u64":7}"#.parse().unwrap())
+        .await
+        .unwrap();
+    assert_eq!(owner, Some(alice.parse().unwrap()));
+    let owner = client
+        .psp34_owner_of(alice, &psp34::Id::U8(7))
+        .await
+        .unwrap();
+    assert_eq!(owner, None);
+}
+
+/// Look up the blocks by height and hash, and over a range of heights.
+#[tokio::test]
+async fn look_up_blocks() {
+    let (full_node_uri, connections) = run_mock_full_node();
+    let mut config = ClientConfig::new(&full_node_uri, "http://127.0.0.1:9/");
+    config.read_backend = ReadBackend::JsonRpc;
+    let client = InteractClient::new(config).unwrap();
+
+    let block = client.get_block_summary(1234).await.unwrap();
+    assert_eq!(block.block_hash, BLOCK_HASH);
+    assert_eq!(block.block_number, 1234);
+    assert_eq!(block.parent_hash, format!("0x{:064x}", 1233));
+    assert_eq!(block.state_root, format!("0x{:064x}", 1_001_234));
+    assert_eq!(block.extrinsics_root, format!("0x{:064x}", 2_001_234));
+    assert_eq!(block.extrinsic_count, 2);
+    // The slot 1234 is of the second of the three validators.
+    assert_eq!(block.author, Some(format!("0x{}", hex::encode([2u8; 32]))));
+    assert_eq!(client.get_block_summary(BLOCK_HASH).await.unwrap(), block);
+
+    let blocks: Vec<BlockSummary> = client
+        .block_summaries(1200..1235, 4)
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(blocks.len(), 35);
+    assert_eq!(blocks.last(), Some(&block));
+    for (height, pair) in (1201..).zip(blocks.windows(2)) {
+        assert_eq!(pair[1].block_number, height);
+        assert_eq!(pair[1].parent_hash, pair[0].block_hash);
+    }
+
+    match client.get_block_summary(1235).await {
+        Err(Error::Rpc { .. }) => (),
+        other => panic!("unexpected result: {:?}", other),
+    }
+    let blocks: Vec<Result<BlockSummary>> = client.block_summaries(1234..1236, 2).collect().await;
+    assert!(blocks[0].is_ok());
+    assert!(blocks[1].is_err());
+
+    // Every request, including the concurrent ones, went through one connection.
+    assert_eq!(connections.load(Ordering::SeqCst), 1);
+}
+
+/// Simulate instantiating a contract, uploading its code or from its code hash.
+#[tokio::test]
+async fn dry_run_instantiations() {
+    let (full_node_uri, _) = run_mock_full_node();
+    let metadata = ContractMetadata::from_file(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../deno-http-server/contracts/simple_counter.contract"
+    ))
+    .unwrap();
+    let client = InteractClient::new(ClientConfig::new(&full_node_uri, "http://127.0.0.1:9/"))
+        .unwrap()
+        .with_metadata(Contract::SIMPLE_COUNTER, metadata);
+    let alice = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY";
+    let counter = Contract::new(Contract::SIMPLE_COUNTER);
+
+    let dry_run = client
+        .dry_run_instantiate(
+            alice,
+            &counter,
+            &[0u64.into()],
+            100_000_000_000_000_000_000,
+            b"salt",
+        )
+        .await
+        .unwrap();
+    assert!(dry_run.is_ok());
+    assert_eq!(dry_run.gas_consumed, 4_000_000_000);
+    assert_eq!(dry_run.gas_required, 5_000_000_000);
+    assert_eq!(
+        dry_run.storage_deposit,
+        StorageDeposit::Charge(10_000_000_000_000_000_000)
+    );
+    assert_eq!(
+        dry_run.storage_deposit_limit(),
+        Some(10_000_000_000_000_000_000)
+    );
+    assert_eq!(dry_run.contract_addr.as_deref(), Some(alice));
+    assert!(dry_run.return_value.is_none());
+
+    let dry_run = client
+        .dry_run_instantiate(
+            alice,
+            &counter.clone().with_code_hash(CODE_HASH),
+            &[0u64.into()],
+            0,
+            b"",
+        )
+        .await
+        .unwrap();
+    assert!(dry_run.is_ok());
+    assert_eq!(
+        dry_run.storage_deposit,
+        StorageDeposit::Charge(1_000_000_000_000_000_000)
+    );
+
+    // No code is stored under the hash.
+    let unknown = format!("0x{}", "00".repeat(32));
+    let dry_run = client
+        .dry_run_instantiate(
+            alice,
+            &counter.clone().with_code_hash(&unknown),
+            &[0u64.into()],
+            0,
+            b"",
+        )
+        .await
+        .unwrap();
+    assert!(dry_run.contract_addr.is_none());
+    match dry_run.check() {
+        Err(Error::ContractReverted(reason)) => assert!(reason.contains("Module")),
+        other => panic!("unexpected result: {:?}", other),
+    }
+
+    // The constructor takes `init_count`.
+    match client
+        .dry_run_instantiate(alice, &counter, &[], 0, b"")
+        .await
+    {
+        Err(Error::InvalidCall(_)) => (),
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
@@ -0,0 +1,36 @@
+mod common;
+
+use pdao_polkadot_interact::*;
+use serde_json::json;
+
+/// Give the http server the credential of each kind of signer.
+#[test]
+fn signer_credentials() {
+    let credential = MnemonicSigner::new("//Alice").credential().unwrap();
+    assert_eq!(
+        serde_json::to_value(&credential).unwrap(),
+        json!({ "mnemonic": "//Alice" })
+    );
+
+    let keystore = json!({
+        "address": "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY",
+        "encoded": "0x1234",
+        "encoding": { "content": ["pkcs8", "sr25519"], "type": ["scrypt", "xsalsa20-poly1305"], "version": "3" },
+        "meta": {},
+    });
+    let path = common::temp_path("signer_credentials", "json");
+    std::fs::write(&path, keystore.to_string()).unwrap();
+    let credential = KeystoreSigner::new(&path, "password").credential().unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(
+        serde_json::to_value(&credential).unwrap(),
+        json!({ "keystore": keystore, "password": "password" })
+    );
+    // The secrets are not printed.
+    assert!(!format!("{:?}", credential).contains("password"));
+
+    match EnvSigner::new("PDAO_POLKADOT_UNSET_SIGNER").credential() {
+        Err(Error::MissingSigner(_)) => (),
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
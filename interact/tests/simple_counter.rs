@@ -0,0 +1,51 @@
+use pdao_polkadot_interact::simple_counter::*;
+use pdao_polkadot_interact::*;
+
+/// `SimpleCounterClient` covers every message of the contract but `only_auth`.
+#[test]
+fn cover_simple_counter_messages() {
+    let metadata = ContractMetadata::from_file(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../deno-http-server/contracts/simple_counter.contract"
+    ))
+    .unwrap();
+    let labels: Vec<_> = metadata
+        .spec
+        .messages
+        .iter()
+        .map(|x| x.label.as_str())
+        .filter(|x| *x != "only_auth")
+        .collect();
+    assert_eq!(labels, MESSAGES);
+
+    let error = metadata
+        .types
+        .iter()
+        .find(|x| x.ty.path.last().map(|x| x.as_str()) == Some("Error"))
+        .unwrap();
+    match &error.ty.def {
+        metadata::TypeDef::Variant { variants } => {
+            for variant in variants {
+                variant.name.parse::<CounterError>().unwrap();
+            }
+        }
+        other => panic!("unexpected type: {:?}", other),
+    }
+}
+
+/// Decode the reason of a revert into the error of the contract.
+#[test]
+fn decode_counter_error() {
+    match CallError::from(Error::ContractReverted("ValueIsOver10".to_owned())) {
+        CallError::Contract(CounterError::ValueIsOver10) => (),
+        other => panic!("unexpected error: {:?}", other),
+    }
+    match CallError::from(Error::ContractReverted("{\"module\":1}".to_owned())) {
+        CallError::Interact(Error::ContractReverted(_)) => (),
+        other => panic!("unexpected error: {:?}", other),
+    }
+    match CallError::from(Error::Backend("timeout".to_owned())) {
+        CallError::Interact(Error::Backend(_)) => (),
+        other => panic!("unexpected error: {:?}", other),
+    }
+}
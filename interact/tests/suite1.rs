@@ -28,23 +28,27 @@ impl Config {
         )
         .expect("Failed to parse the config")
     }
+
+    pub fn client(&self) -> InteractClient {
+        let mut client_config =
+            ClientConfig::new(&self.test_shibuya_node_url, &self.test_http_server_url);
+        client_config.decimals = self.planck_to_one.into();
+        InteractClient::new(client_config).unwrap()
+    }
+
+    /// The test account signs with the mnemonic in `SIGNER_MNEMONIC`.
+    pub fn signer(&self) -> EnvSigner {
+        EnvSigner::new("SIGNER_MNEMONIC")
+    }
 }
 
 /// Return block hash and timestamp from the latest block height.
 #[tokio::test]
 async fn check_connection() {
     let config = Config::read_from_env();
-    let height = get_current_height(&config.test_shibuya_node_url, &config.test_http_server_url)
-        .await
-        .unwrap()
-        .unwrap();
-    let _block = get_block(
-        &config.test_shibuya_node_url,
-        &config.test_http_server_url,
-        height,
-    )
-    .await
-    .unwrap();
+    let client = config.client();
+    let height = client.get_current_height().await.unwrap();
+    let _block = client.get_block(height).await.unwrap();
 
     // println!("{:?}", block);
 }
@@ -53,16 +57,9 @@ async fn check_connection() {
 #[tokio::test]
 async fn check_block_number() {
     let config = Config::read_from_env();
-    let first_block =
-        get_current_height(&config.test_shibuya_node_url, &config.test_http_server_url)
-            .await
-            .unwrap()
-            .unwrap();
-    let second_block =
-        get_current_height(&config.test_shibuya_node_url, &config.test_http_server_url)
-            .await
-            .unwrap()
-            .unwrap();
+    let client = config.client();
+    let first_block = client.get_current_height().await.unwrap();
+    let second_block = client.get_current_height().await.unwrap();
 
     assert!(first_block < second_block);
 }
@@ -71,13 +68,11 @@ async fn check_block_number() {
 #[tokio::test]
 async fn check_account() {
     let config = Config::read_from_env();
-    let account = query_account(
-        &config.test_shibuya_node_url,
-        &config.test_http_server_url,
-        &config.account_public,
-    )
-    .await
-    .unwrap();
+    let account = config
+        .client()
+        .query_account(&config.account_public)
+        .await
+        .unwrap();
     let account_balance = account.native_token.parse::<u64>().unwrap();
 
     assert!(account_balance > 1_000_000_000);
@@ -88,18 +83,13 @@ async fn check_account() {
 async fn transfer_token() {
     let config = Config::read_from_env();
     let amount_to_transfer = 123_456_789;
-    let planck_to_one = config.planck_to_one;
-    let result = transfer_native_token(
-        &config.test_shibuya_node_url,
-        &config.test_http_server_url,
-        &config.account_public,
-        amount_to_transfer,
-        planck_to_one,
-    )
-    .await
-    .unwrap();
-
-    println!("Transaction hash: {}", result);
+    let result = config
+        .client()
+        .transfer_native_token(&config.signer(), &config.account_public, amount_to_transfer)
+        .await
+        .unwrap();
+
+    println!("Transaction hash: {}", result.tx_hash());
 }
 
 /// Query the state of deployed contract.
@@ -107,15 +97,15 @@ async fn transfer_token() {
 async fn check_contract_state() {
     let config = Config::read_from_env();
     let field = "auth"; // get_count
-    let result = query_contract_state(
-        &config.test_shibuya_node_url,
-        &config.test_http_server_url,
-        &config.contract_address,
-        Contract::SimpleCounter,
-        field,
-    )
-    .await
-    .unwrap();
+    let result = config
+        .client()
+        .query_contract_state(
+            &Contract::new(Contract::SIMPLE_COUNTER).at(&config.contract_address),
+            field,
+            Vec::new(),
+        )
+        .await
+        .unwrap();
 
     assert_eq!(
         result.output[0],
@@ -130,16 +120,16 @@ async fn execute_contract() {
     // No argument in fn increment().
     let argument = Vec::new();
     let method_name = "increment";
-    let result = execute_contract_method(
-        &config.test_shibuya_node_url,
-        &config.test_http_server_url,
-        &config.contract_address,
-        Contract::SimpleCounter,
-        method_name,
-        argument,
-    )
-    .await
-    .unwrap();
+    let result = config
+        .client()
+        .execute_contract_method(
+            &config.signer(),
+            &Contract::new(Contract::SIMPLE_COUNTER).at(&config.contract_address),
+            method_name,
+            argument,
+        )
+        .await
+        .unwrap();
 
     assert_eq!(result.message_name, method_name);
     assert_eq!(result.message_type, "tx");
@@ -149,16 +139,16 @@ async fn execute_contract() {
 #[tokio::test]
 async fn deploy_contract_with_name() {
     let config = Config::read_from_env();
-    let mut argument = Vec::new();
-    argument.push("5");
-    let _result = deploy_contract(
-        &config.test_shibuya_node_url,
-        &config.test_http_server_url,
-        Contract::SimpleCounter,
-        argument,
-    )
-    .await
-    .unwrap();
+    let argument = vec![Argument::from(5u64)];
+    let _result = config
+        .client()
+        .deploy_contract(
+            &config.signer(),
+            &Contract::new(Contract::SIMPLE_COUNTER),
+            argument,
+        )
+        .await
+        .unwrap();
 }
 
 /// Deploy contract from the contract hash.
@@ -167,16 +157,16 @@ async fn deploy_contract_with_name() {
 #[tokio::test]
 async fn deploy_contract_with_hash() {
     let config = Config::read_from_env();
-    let mut argument = Vec::new();
-    argument.push("5");
+    let argument = vec![Argument::from(5u64)];
     let salt = ""; // Empty string for Null in ts.
-    let _result = deploy_contract_with_code_hash(
-        &config.test_shibuya_node_url,
-        &config.test_http_server_url,
-        Contract::SimpleCounter,
-        argument,
-        salt,
-    )
-    .await
-    .unwrap();
+    let _result = config
+        .client()
+        .deploy_contract_with_code_hash(
+            &config.signer(),
+            &Contract::new(Contract::SIMPLE_COUNTER),
+            argument,
+            salt,
+        )
+        .await
+        .unwrap();
 }
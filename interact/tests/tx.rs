@@ -0,0 +1,173 @@
+mod common;
+
+use pdao_polkadot_interact::*;
+use serde_json::{json, Value};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Serve each body to a request of the http server, in order, and repeat the last one.
+/// Return the address of the server and the bodies of the requests it receives.
+fn mock_http_server(bodies: Vec<Value>) -> (String, Arc<Mutex<Vec<Value>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = format!("http://{}/", listener.local_addr().unwrap());
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let received = Arc::clone(&requests);
+    std::thread::spawn(move || {
+        for (i, stream) in listener.incoming().enumerate() {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if let Some(length) = line.to_lowercase().strip_prefix("content-length:") {
+                    content_length = length.trim().parse().unwrap();
+                }
+                if line == "\r\n" {
+                    break;
+                }
+            }
+            let mut request = Vec::new();
+            reader
+                .by_ref()
+                .take(content_length)
+                .read_to_end(&mut request)
+                .unwrap();
+            received
+                .lock()
+                .unwrap()
+                .push(serde_json::from_slice(&request).unwrap());
+            let body = bodies[i.min(bodies.len() - 1)].to_string();
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\
+                 Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            )
+            .unwrap();
+        }
+    });
+    (address, requests)
+}
+
+fn inclusion(finalized: bool) -> Value {
+    json!({
+        "success": true,
+        "data": {
+            "inclusion": {
+                "blockHash": "0xab",
+                "blockNumber": 101,
+                "extrinsicIndex": 2,
+                "dispatchError": null,
+                "fee": "1250000000",
+                "events": [{
+                    "section": "system",
+                    "method": "ExtrinsicSuccess",
+                    "data": ["{\"weight\":1}"],
+                }],
+                "finalized": finalized,
+            },
+            "resumeHeight": 101,
+        },
+    })
+}
+
+/// Wait until the transaction is in a block, then until it is finalized.
+#[tokio::test]
+async fn wait_for_finality() {
+    let pending = json!({ "success": true, "data": { "inclusion": null, "resumeHeight": 101 } });
+    let (http_server, requests) = mock_http_server(vec![
+        pending.clone(),
+        pending,
+        inclusion(false),
+        inclusion(false),
+        inclusion(true),
+    ]);
+    let client = InteractClient::new(ClientConfig::new("ws://127.0.0.1:1", &http_server)).unwrap();
+    let handle = client
+        .track("0x1234", 100)
+        .with_poll_interval(Duration::from_millis(10));
+
+    let in_block = handle.wait_in_block(Duration::from_secs(5)).await.unwrap();
+    assert_eq!(in_block.status(), TxStatus::InBlock);
+    assert_eq!(in_block.extrinsic_index, 2);
+    assert_eq!(in_block.fee, "1250000000");
+    assert_eq!(in_block.events[0].method, "ExtrinsicSuccess");
+
+    let finalized = handle.wait_finalized(Duration::from_secs(5)).await.unwrap();
+    assert_eq!(finalized.status(), TxStatus::Finalized);
+    assert_eq!(finalized.block_hash, "0xab");
+
+    // The polls after the first one resume from the block which it has not settled.
+    let from_heights: Vec<Value> = requests
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|x| x["fromHeight"].clone())
+        .collect();
+    assert_eq!(
+        from_heights,
+        vec![json!(100), json!(101), json!(101), json!(100), json!(101)]
+    );
+}
+
+/// Fail when the transaction is not finalized before the deadline.
+#[tokio::test]
+async fn time_out_waiting_for_finality() {
+    let (http_server, _) = mock_http_server(vec![inclusion(false)]);
+    let client = InteractClient::new(ClientConfig::new("ws://127.0.0.1:1", &http_server)).unwrap();
+    let handle = client
+        .track("0x1234", 100)
+        .with_poll_interval(Duration::from_millis(10));
+
+    match handle.wait_finalized(Duration::from_millis(200)).await {
+        Err(Error::TxTimeout(_)) => (),
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+/// Append every deployment to the registry of the config.
+#[tokio::test]
+async fn record_deployments() {
+    let (http_server, _) = mock_http_server(vec![json!({
+        "success": true,
+        "data": {
+            "contractName": "simple_counter",
+            "contractAddr": "aeQtHkL7ENgfVYwiMZNYgz2GeRa75B2cSaVz1AFjZ9omRtv",
+            "codeHash": "0x6ecc",
+            "txHash": "0x1234",
+            "blockNumber": 100,
+        },
+    })]);
+    let registry = common::temp_path("record_deployments", "json");
+    let mut config = ClientConfig::new("ws://127.0.0.1:1", &http_server);
+    config.registry = Some(registry.clone());
+    let client = InteractClient::new(config).unwrap();
+    let signer = MnemonicSigner::new(
+        "bottom drive obey lake curtain smoke basket hold race lonely fit walk",
+    );
+    let counter = Contract::new(Contract::SIMPLE_COUNTER).with_code_hash("0x6ecc");
+
+    client
+        .deploy_contract_with_code_hash(&signer, &counter, Vec::new(), "0x")
+        .await
+        .unwrap();
+    client
+        .deploy_contract_with_code_hash(&signer, &counter, Vec::new(), "0x01")
+        .await
+        .unwrap();
+
+    let deployments = registry::DeploymentRegistry::open(&registry).unwrap();
+    std::fs::remove_file(&registry).unwrap();
+    assert_eq!(deployments.deployments().len(), 2);
+    let deployment = deployments.find(Contract::SIMPLE_COUNTER).unwrap();
+    assert_eq!(
+        deployment.contract_addr(),
+        "aeQtHkL7ENgfVYwiMZNYgz2GeRa75B2cSaVz1AFjZ9omRtv"
+    );
+    assert_eq!(deployment.contract().code_hash.as_deref(), Some("0x6ecc"));
+    assert_eq!(deployment.block_number, 100);
+}